@@ -0,0 +1,50 @@
+#![no_main]
+
+use dtb_parser::traits::HasNamedProperty;
+use dtb_parser::DeviceTree;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(tree) = DeviceTree::from_bytes(data) else {
+        return;
+    };
+    for node in &tree {
+        for prop in node.props() {
+            let _ = prop.value().to_string();
+            let _ = prop.reg_entries();
+            prop.resolve_phandle(&tree);
+            prop.as_u64();
+            prop.as_str_list().for_each(drop);
+            prop.as_cells().into_iter().flatten().for_each(drop);
+        }
+        node.path();
+        tree.node_ref(node.node());
+
+        let _ = node.interrupts();
+        node.find_prop("interrupt-map")
+            .map(|_| tree.map_interrupt(node.node(), &[0, 0, 0], &[1]));
+        tree.cpu_regs(node.node());
+        tree.cpu_to_dma(node.node(), 0);
+        tree.dma_windows(node.node());
+
+        if let Ok(clocks) = node.clocks() {
+            for clock in clocks {
+                let _ = clock.provider().rate();
+            }
+        }
+        let _ = node.assigned_clocks();
+        let _ = node.gpios("");
+        let _ = node.gpio_hogs();
+        let _ = node.gpio_ranges();
+        let _ = node.pinctrl_states();
+        let _ = node.pin_config();
+        let _ = node.memory_regions();
+    }
+    if let Ok(reserved) = tree.reserved_memory() {
+        let _ = reserved.static_ranges();
+    }
+    let _ = tree.memory_map();
+    let _ = tree.to_dts();
+    // whatever is accepted has to be written back into a valid blob
+    assert!(DeviceTree::from_bytes(&tree.to_bytes().unwrap()).is_ok());
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+use dtb_parser::walker::Token;
+use dtb_parser::Fdt;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fdt) = Fdt::from_bytes(data) else {
+        return;
+    };
+    fdt.memory_reservations().for_each(drop);
+    for token in fdt.tokens() {
+        if let Ok(Token::Property(prop)) = token {
+            prop.as_str_list().for_each(drop);
+            prop.as_cells().into_iter().flatten().for_each(drop);
+        }
+    }
+    if let Ok(root) = fdt.root() {
+        for child in root.children().flatten() {
+            child.props().for_each(drop);
+            if let Some(reg) = child.reg() {
+                reg.for_each(drop);
+            }
+        }
+    }
+});
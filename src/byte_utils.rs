@@ -1,20 +1,16 @@
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 use alloc::vec::Vec;
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
 pub(crate) const BLOCK_SIZE: usize = 4;
 
-pub(crate) fn align_block(index: usize) -> usize {
-    index / (BLOCK_SIZE)
-}
-
 pub(crate) fn locate_block(index: usize) -> usize {
     index * (BLOCK_SIZE)
 }
 
 pub(crate) fn align_size(raw_size: usize) -> usize {
-    (raw_size + BLOCK_SIZE - 1) / BLOCK_SIZE
+    raw_size.div_ceil(BLOCK_SIZE)
 }
 
 pub(crate) fn read_aligned_block(data: &[u8], index: usize) -> Option<[u8; BLOCK_SIZE]> {
@@ -32,16 +28,17 @@ pub(crate) fn read_aligned_block(data: &[u8], index: usize) -> Option<[u8; BLOCK
 }
 
 pub(crate) fn read_aligned_be_u32(data: &[u8], index: usize) -> Option<u32> {
-    read_aligned_block(data, index).map(|block| u32::from_be_bytes(block))
+    read_aligned_block(data, index).map(u32::from_be_bytes)
 }
 
+#[cfg(feature = "alloc")]
 pub(crate) fn read_aligned_be_big_number(
     data: &[u8],
     index: usize,
     block_size: usize,
 ) -> Option<u128> {
     match block_size {
-        0 | 1 | 2 => read_aligned_be_number(data, index, block_size).map(|f| f as u128),
+        0..=2 => read_aligned_be_number(data, index, block_size).map(|f| f as u128),
         3 | 4 => {
             let mut num = 0u128;
             for i in 0..block_size {
@@ -72,19 +69,27 @@ pub(crate) fn read_aligned_be_number(data: &[u8], index: usize, block_size: usiz
     }
 }
 
+#[cfg(feature = "alloc")]
+pub(crate) fn write_be_number(buffer: &mut Vec<u8>, value: u128, block_size: usize) -> Option<()> {
+    if block_size > 4 || (block_size < 4 && value >> (32 * block_size) != 0) {
+        return None;
+    }
+    for i in (0..block_size).rev() {
+        buffer.extend_from_slice(&((value >> (32 * i)) as u32).to_be_bytes());
+    }
+    Some(())
+}
+
 pub(crate) fn read_name(data: &[u8], offset: usize) -> Option<&str> {
     let first = offset;
     if first > data.len() {
         None
     } else {
         let mut end = first;
-        while data[end] != '\0' as u8 {
-            end = end + 1;
-        }
-        match core::str::from_utf8(&data[first..end]) {
-            Ok(s) => Some(s),
-            _ => None,
+        while *data.get(end)? != b'\0' {
+            end += 1;
         }
+        core::str::from_utf8(&data[first..end]).ok()
     }
 }
 
@@ -92,6 +97,7 @@ pub(crate) fn read_aligned_name(data: &[u8], index: usize) -> Option<&str> {
     read_name(data, locate_block(index))
 }
 
+#[cfg(feature = "alloc")]
 pub(crate) fn read_aligned_sized_strings(
     data: &[u8],
     index: usize,
@@ -107,7 +113,7 @@ pub(crate) fn read_aligned_sized_strings(
         while current < first + size {
             if data[current] == b'\0' {
                 // collect
-                let value = core::str::from_utf8(&data[last..current]).unwrap();
+                let value = core::str::from_utf8(&data[last..current]).ok()?;
                 res.push(value);
                 last = current + 1;
             }
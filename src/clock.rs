@@ -0,0 +1,255 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::error::{ErrorKind, Result};
+use crate::traits::HasNamedProperty;
+
+const CLOCK_CELLS: &str = "#clock-cells";
+// fixed-factor clocks followed before giving up on a loop of parents
+const MAX_PARENT_HOPS: usize = 64;
+
+/// How a clock provider gets its rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockKind {
+    /// A `fixed-clock` running at `frequency` Hz, give or take `accuracy` ppb
+    Fixed {
+        /// The `clock-frequency`
+        frequency: u64,
+        /// The `clock-accuracy`
+        accuracy: Option<u32>,
+    },
+    /// A `fixed-factor-clock` running at the rate of its parent multiplied by `mult` and divided by `div`
+    FixedFactor {
+        /// The `clock-mult`
+        mult: u32,
+        /// The `clock-div`
+        div: u32,
+    },
+    /// A clock whose rate is up to its driver
+    Other,
+}
+
+/// A node with `#clock-cells`, which provides clocks to other nodes
+#[derive(Clone)]
+pub struct ClockProvider<'b, 'a> {
+    node: NodeRef<'b, 'a>,
+}
+
+impl<'b, 'a> ClockProvider<'b, 'a> {
+    /// Get the provider node
+    pub fn node(&self) -> &NodeRef<'b, 'a> {
+        &self.node
+    }
+
+    /// Get its `#clock-cells`, the number of arguments selecting one of its outputs
+    pub fn cells(&self) -> u32 {
+        self.node.prop_u32(CLOCK_CELLS).unwrap_or(0)
+    }
+
+    /// Decode the bindings of the fixed clocks
+    pub fn kind(&self) -> ClockKind {
+        let node = self.node.node();
+        if node.is_compatible("fixed-clock") {
+            if let Some(frequency) = node.find_prop("clock-frequency").and_then(|it| it.as_u64()) {
+                return ClockKind::Fixed {
+                    frequency,
+                    accuracy: node.prop_u32("clock-accuracy"),
+                };
+            }
+        }
+        if node.is_compatible("fixed-factor-clock") {
+            if let (Some(mult), Some(div)) =
+                (node.prop_u32("clock-mult"), node.prop_u32("clock-div"))
+            {
+                return ClockKind::FixedFactor { mult, div };
+            }
+        }
+        ClockKind::Other
+    }
+
+    /// Get its `clock-output-names`
+    pub fn output_names(&self) -> Vec<&'b str> {
+        let node = self.node.node();
+        node.find_prop("clock-output-names")
+            .map(|names| names.as_str_list().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the name of its output `index`
+    pub fn output_name(&self, index: u32) -> Option<&'b str> {
+        self.output_names().get(index as usize).copied()
+    }
+
+    /// Work out its rate in Hz through the fixed clocks, `None` if a driver decides it
+    pub fn rate(&self) -> Result<Option<u64>> {
+        let mut provider = self.clone();
+        let (mut mult, mut div) = (1u128, 1u128);
+        for _ in 0..MAX_PARENT_HOPS {
+            match provider.kind() {
+                ClockKind::Fixed { frequency, .. } => {
+                    let rate = frequency as u128 * mult / div;
+                    return Ok(Some(
+                        u64::try_from(rate).map_err(|_| ErrorKind::ParsingFailed)?,
+                    ));
+                }
+                ClockKind::FixedFactor { mult: m, div: d } => {
+                    mult = mult
+                        .checked_mul(m as u128)
+                        .ok_or(ErrorKind::ParsingFailed)?;
+                    div = div.checked_mul(d as u128).ok_or(ErrorKind::ParsingFailed)?;
+                    if div == 0 {
+                        return Err(ErrorKind::ParsingFailed.into());
+                    }
+                    provider = match provider.node.clocks()?.into_iter().next() {
+                        Some(parent) => parent.provider,
+                        None => return Ok(None),
+                    };
+                }
+                ClockKind::Other => return Ok(None),
+            }
+        }
+        Err(ErrorKind::ParsingFailed.into())
+    }
+}
+
+/// A clock consumed by a node: an entry of its `clocks`, named by its `clock-names`
+#[derive(Clone)]
+pub struct Clock<'b, 'a> {
+    provider: ClockProvider<'b, 'a>,
+    args: Vec<u32>,
+    name: Option<&'b str>,
+}
+
+impl<'b, 'a> Clock<'b, 'a> {
+    /// Get the provider of the clock
+    pub fn provider(&self) -> &ClockProvider<'b, 'a> {
+        &self.provider
+    }
+
+    /// Get the arguments for the provider
+    pub fn args(&self) -> &[u32] {
+        &self.args
+    }
+
+    /// Get the output of the provider, its first argument or 0 for a provider of a single clock
+    pub fn index(&self) -> u32 {
+        self.args.first().copied().unwrap_or(0)
+    }
+
+    /// Get the name the consumer gives it
+    pub fn name(&self) -> Option<&'b str> {
+        self.name
+    }
+
+    /// Get the name the provider gives its output
+    pub fn output_name(&self) -> Option<&'b str> {
+        self.provider.output_name(self.index())
+    }
+}
+
+/// A clock to configure before use, from `assigned-clocks` and its companion properties
+#[derive(Clone)]
+pub struct AssignedClock<'b, 'a> {
+    clock: Clock<'b, 'a>,
+    rate: Option<u64>,
+    parent: Option<Clock<'b, 'a>>,
+}
+
+impl<'b, 'a> AssignedClock<'b, 'a> {
+    /// Get the clock to configure
+    pub fn clock(&self) -> &Clock<'b, 'a> {
+        &self.clock
+    }
+
+    /// Get the rate to set from `assigned-clock-rates`, `None` if it is left alone
+    pub fn rate(&self) -> Option<u64> {
+        self.rate
+    }
+
+    /// Get the parent to switch to from `assigned-clock-parents`, `None` if it is left alone
+    pub fn parent(&self) -> Option<&Clock<'b, 'a>> {
+        self.parent.as_ref()
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Get all the nodes providing clocks, those with `#clock-cells`
+    pub fn clock_providers(&self) -> Vec<ClockProvider<'_, 'a>> {
+        self.into_iter()
+            .filter(|node| node.find_prop(CLOCK_CELLS).is_some())
+            .map(|node| ClockProvider { node })
+            .collect()
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Resolve its `clocks`, named by its `clock-names`
+    pub fn clocks(&self) -> Result<Vec<Clock<'b, 'a>>> {
+        let clocks = self
+            .clock_list("clocks", true)
+            .map_err(|err| err.in_node(&self.path()))?;
+        Ok(clocks.into_iter().flatten().collect())
+    }
+
+    /// Find the clock named `name` by its `clock-names`
+    pub fn clock(&self, name: &str) -> Result<Option<Clock<'b, 'a>>> {
+        Ok(self
+            .clocks()?
+            .into_iter()
+            .find(|clock| clock.name() == Some(name)))
+    }
+
+    /// Resolve its `assigned-clocks` with the `assigned-clock-rates` and `assigned-clock-parents`
+    pub fn assigned_clocks(&self) -> Result<Vec<AssignedClock<'b, 'a>>> {
+        self.read_assigned_clocks()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    fn read_assigned_clocks(&self) -> Result<Vec<AssignedClock<'b, 'a>>> {
+        let rates = match self.find_prop("assigned-clock-rates") {
+            Some(rates) => rates.cells().map_err(|err| err.in_property(rates.name()))?,
+            None => Vec::new(),
+        };
+        let mut parents = self.clock_list("assigned-clock-parents", false)?;
+        let clocks = self.clock_list("assigned-clocks", false)?;
+        let mut assigned = Vec::new();
+        for (index, clock) in clocks.into_iter().enumerate() {
+            if let Some(clock) = clock {
+                assigned.push(AssignedClock {
+                    clock,
+                    // a zero rate leaves the clock at its rate
+                    rate: rates
+                        .get(index)
+                        .filter(|rate| **rate != 0)
+                        .map(|it| *it as u64),
+                    parent: parents.get_mut(index).and_then(Option::take),
+                });
+            }
+        }
+        Ok(assigned)
+    }
+
+    // the entries of a list of clocks, `None` for the empty ones so they keep their place
+    fn clock_list(&self, list: &str, named: bool) -> Result<Vec<Option<Clock<'b, 'a>>>> {
+        let mut names = self
+            .node()
+            .find_prop("clock-names")
+            .filter(|_| named)
+            .map(|names| names.as_str_list());
+        self.phandle_args(list, CLOCK_CELLS)
+            .map(|entry| {
+                let name = names.as_mut().and_then(Iterator::next);
+                Ok(entry?.map(|entry| Clock {
+                    provider: ClockProvider {
+                        node: entry.provider().clone(),
+                    },
+                    args: entry.args().to_vec(),
+                    name,
+                }))
+            })
+            .collect()
+    }
+}
@@ -1,58 +1,114 @@
 #[cfg(not(feature = "std"))]
-use alloc::{collections::VecDeque, string::String, vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+    string::String,
+    vec::Vec,
+};
 #[cfg(not(feature = "std"))]
-use core::fmt::{Display, Formatter};
+use core::{
+    fmt::{Display, Formatter},
+    ops::Deref,
+};
 #[cfg(feature = "std")]
-use std::fmt::{Display, Formatter};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+    string::String,
+    vec::Vec,
+};
 #[cfg(feature = "std")]
-use std::{collections::VecDeque, string::String, vec, vec::Vec};
+use std::{
+    fmt::{Display, Formatter},
+    ops::Deref,
+};
 
-use crate::error::{DeviceTreeError, Result};
+use crate::error::{ErrorKind, Result};
 use crate::header::DeviceTreeHeader;
+#[cfg(not(feature = "std"))]
+use crate::header::HEADER_SIZE;
 use crate::node::DeviceTreeNode;
-use crate::traits::HasNamedChildNode;
+use crate::prop::{NodeProperty, PropertyValue};
+use crate::reservation::MemoryReservation;
+use crate::schema::{DefaultRegistry, PropertyRegistry};
+use crate::traits::{HasNamedChildNode, HasNamedProperty};
+use crate::walker::{Fdt, Validation};
 
 /// The tree structure
 /// Reads data from a slice of bytes and parses into [DeviceTree]
 /// Indexed by nodes and properties' names or by path for the whole tree
-pub struct DeviceTree {
+///
+/// Names and property values are borrowed from the blob rather than copied
+pub struct DeviceTree<'a> {
     header: DeviceTreeHeader,
-    root: DeviceTreeNode,
+    reservations: Vec<MemoryReservation>,
+    root: DeviceTreeNode<'a>,
+    // phandle and the child indices leading from the root to its node
+    phandles: BTreeMap<u32, Vec<usize>>,
 }
 
-impl DeviceTree {
+impl<'a> DeviceTree<'a> {
     /// Parses a slice of bytes and constructs [DeviceTree]
     /// The structure should live as long as the `data`
-    pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        let magic = &data[0..4];
-        if magic != [0xd0, 0x0d, 0xfe, 0xed] {
-            return Err(DeviceTreeError::InvalidMagicNumber);
-        }
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self> {
+        Self::from_bytes_with(data, Validation::Lenient, &DefaultRegistry)
+    }
 
-        let header = DeviceTreeHeader::from_bytes(data)?;
+    /// Parses a slice of bytes whose header is checked as thoroughly as `validation` asks,
+    /// its properties are decoded by the decoders `registry` picks
+    pub fn from_bytes_with(
+        data: &'a [u8],
+        validation: Validation,
+        registry: &dyn PropertyRegistry,
+    ) -> Result<Self> {
+        let fdt = Fdt::from_bytes_with(data, validation)?;
+        let reservations = fdt.memory_reservations().collect::<Result<Vec<_>>>()?;
+        let root = DeviceTreeNode::from_fdt(fdt.root()?, registry)?;
+
+        let phandles = Self::index_phandles(&root);
+
+        Ok(Self {
+            header: fdt.header,
+            reservations,
+            root,
+            phandles,
+        })
+    }
 
-        let root = DeviceTreeNode::from_bytes(
-            data,
-            &header,
-            header.off_dt_struct as usize,
-            InheritedValues::new(),
-        )?;
+    // for trees which are not read from a blob, the header is the one they serialize to
+    pub(crate) fn from_parts(
+        reservations: Vec<MemoryReservation>,
+        root: DeviceTreeNode<'a>,
+    ) -> Self {
+        let phandles = Self::index_phandles(&root);
+        let mut tree = Self {
+            header: DeviceTreeHeader::default(),
+            reservations,
+            root,
+            phandles,
+        };
+        tree.refresh_header();
+        tree
+    }
 
-        Ok(Self { header, root })
+    // the header the tree serializes to, kept as it is if the tree cannot be serialized
+    fn refresh_header(&mut self) {
+        if let Some(header) = self
+            .to_bytes()
+            .ok()
+            .and_then(|blob| DeviceTreeHeader::from_bytes(&blob).ok())
+        {
+            self.header = header;
+        }
     }
 
     #[cfg(not(feature = "std"))]
     /// Parses from address where a device tree blob is located at
-    pub fn from_address(addr: usize) -> Result<Self> {
-        let header_bytes = unsafe { core::slice::from_raw_parts(addr as *const u8, 40) };
-        let magic = &header_bytes[0..4];
-        if magic != [0xd0, 0x0d, 0xfe, 0xed] {
-            return Err(DeviceTreeError::InvalidMagicNumber);
-        }
-        let header = DeviceTreeHeader::from_bytes(header_bytes)?;
-        let data =
-            unsafe { core::slice::from_raw_parts(addr as *const u8, header.total_size as usize) };
-        Self::from_bytes(data)
+    pub fn from_address(addr: usize) -> Result<DeviceTree<'static>> {
+        let header_bytes = unsafe { core::slice::from_raw_parts(addr as *const u8, HEADER_SIZE) };
+        let total_size = DeviceTreeHeader::from_bytes(header_bytes)?.total_size as usize;
+        let data = unsafe { core::slice::from_raw_parts(addr as *const u8, total_size) };
+        DeviceTree::from_bytes(data)
     }
 
     /// Its magic number extracted from the header
@@ -105,13 +161,18 @@ impl DeviceTree {
         self.header.size_dt_struct as usize
     }
 
+    /// Iterate over the ranges reserved by the memory reservation block
+    pub fn memory_reservations(&self) -> impl Iterator<Item = MemoryReservation> + '_ {
+        self.reservations.iter().copied()
+    }
+
     /// Get a reference of the root node
-    pub fn root(&self) -> &DeviceTreeNode {
+    pub fn root(&self) -> &DeviceTreeNode<'a> {
         &self.root
     }
 
     /// Find the node by given node path
-    pub fn find_node(&self, path: &str) -> Option<&DeviceTreeNode> {
+    pub fn find_node(&self, path: &str) -> Option<&DeviceTreeNode<'a>> {
         let mut slices = path.split('/');
         if let Some("") = slices.next() {
             let mut first = &self.root;
@@ -128,11 +189,146 @@ impl DeviceTree {
         }
     }
 
+    /// Find the node which declares the given phandle
+    /// Looked up in the index built while parsing, the tree is scanned if it has been edited since
+    pub fn find_by_phandle(&self, phandle: u32) -> Option<&DeviceTreeNode<'a>> {
+        let indexed = self.phandles.get(&phandle).and_then(|indices| {
+            indices
+                .iter()
+                .try_fold(&self.root, |node, index| node.nodes().get(*index))
+        });
+        match indexed {
+            Some(node) if node.phandle() == Some(phandle) => Some(node),
+            _ => self
+                .into_iter()
+                .find(|node| node.phandle() == Some(phandle))
+                .map(|node| node.node()),
+        }
+    }
+
+    fn index_phandles(root: &DeviceTreeNode<'a>) -> BTreeMap<u32, Vec<usize>> {
+        fn visit(
+            node: &DeviceTreeNode,
+            path: &mut Vec<usize>,
+            index: &mut BTreeMap<u32, Vec<usize>>,
+        ) {
+            if let Some(phandle) = node.phandle() {
+                index.entry(phandle).or_insert_with(|| path.clone());
+            }
+            for (i, child) in node.nodes().iter().enumerate() {
+                path.push(i);
+                visit(child, path, index);
+                path.pop();
+            }
+        }
+        let mut index = BTreeMap::new();
+        visit(root, &mut Vec::new(), &mut index);
+        index
+    }
+
+    // the node declaring `phandle`, with its own ancestors
+    // Follows the index path, the tree is only scanned if it has been edited since
+    pub(crate) fn resolve(&self, phandle: u32) -> Result<NodeRef<'_, 'a>> {
+        self.phandles
+            .get(&phandle)
+            .and_then(|indices| self.node_ref_at(indices))
+            .filter(|node| node.phandle() == Some(phandle))
+            .or_else(|| {
+                self.into_iter()
+                    .find(|node| node.phandle() == Some(phandle))
+            })
+            .ok_or(ErrorKind::UnresolvedPHandle.into())
+    }
+
+    /// Get a handle of a node of this tree, which knows its parent and path
+    /// Returns `None` if the node does not belong to this tree
+    pub fn node_ref<'b>(&'b self, node: &DeviceTreeNode<'a>) -> Option<NodeRef<'b, 'a>> {
+        let indexed = node
+            .phandle()
+            .and_then(|phandle| self.phandles.get(&phandle))
+            .and_then(|indices| self.node_ref_at(indices))
+            .filter(|it| core::ptr::eq(it.node(), node));
+        if indexed.is_some() {
+            return indexed;
+        }
+        let mut indices = Vec::new();
+        Self::locate(&self.root, node, &mut indices)
+            .then(|| self.node_ref_at(&indices))
+            .flatten()
+    }
+
+    // the handle of the node reached from the root through the child `indices`
+    fn node_ref_at<'b>(&'b self, indices: &[usize]) -> Option<NodeRef<'b, 'a>> {
+        indices.iter().try_fold(self.root_ref(), |parent, index| {
+            let node = parent.node.nodes().get(*index)?;
+            Some(NodeRef::child_of(Rc::new(parent), node))
+        })
+    }
+
+    // the child indices leading from `current` down to `target`, false if it is not below
+    fn locate(
+        current: &DeviceTreeNode<'a>,
+        target: &DeviceTreeNode<'a>,
+        indices: &mut Vec<usize>,
+    ) -> bool {
+        if core::ptr::eq(current, target) {
+            return true;
+        }
+        for (i, child) in current.nodes().iter().enumerate() {
+            indices.push(i);
+            if Self::locate(child, target, indices) {
+                return true;
+            }
+            indices.pop();
+        }
+        false
+    }
+
+    fn root_ref(&self) -> NodeRef<'_, 'a> {
+        NodeRef {
+            tree: self,
+            node: &self.root,
+            parent: None,
+            depth: 0,
+        }
+    }
+
+    /// Get a mutable reference of the root node
+    /// The header and the phandle index keep describing the tree before the edits,
+    /// see [DeviceTree::edit] to have them rebuilt
+    pub fn root_mut(&mut self) -> &mut DeviceTreeNode<'a> {
+        &mut self.root
+    }
+
+    /// Edit the tree from its root, then rebuild the header and the phandle index,
+    /// so the header describes the blob it now serializes to
+    pub fn edit<R>(&mut self, edit: impl FnOnce(&mut DeviceTreeNode<'a>) -> R) -> R {
+        let res = edit(&mut self.root);
+        self.phandles = Self::index_phandles(&self.root);
+        self.refresh_header();
+        res
+    }
+
+    /// Find the node by given node path and get a mutable reference of it
+    /// Like [DeviceTree::root_mut], it leaves the header and the phandle index as they were
+    pub fn find_node_mut(&mut self, path: &str) -> Option<&mut DeviceTreeNode<'a>> {
+        let mut slices = path.split('/');
+        if let Some("") = slices.next() {
+            let mut first = &mut self.root;
+            for i in slices {
+                first = first.find_child_mut(i)?;
+            }
+            Some(first)
+        } else {
+            None
+        }
+    }
+
     /// Find the node by given node path with all the nodes traveled
-    pub fn find_along_path(&self, path: &str) -> Option<Vec<&DeviceTreeNode>> {
+    pub fn find_along_path(&self, path: &str) -> Option<Vec<&DeviceTreeNode<'a>>> {
         let mut slices: Vec<&str> = path.split('/').collect();
-        let mut container = Vec::<&DeviceTreeNode>::new();
-        if slices.len() > 0 && self.root.name() == slices[0] {
+        let mut container = Vec::<&DeviceTreeNode<'a>>::new();
+        if !slices.is_empty() && self.root.name() == slices[0] {
             container.push(&self.root);
             if Self::find_along_path_internal(&self.root, &mut slices, 1, &mut container) {
                 Some(container)
@@ -145,10 +341,10 @@ impl DeviceTree {
     }
 
     fn find_along_path_internal<'tree>(
-        node: &'tree DeviceTreeNode,
+        node: &'tree DeviceTreeNode<'a>,
         slices: &mut [&str],
         index: usize,
-        container: &mut Vec<&'tree DeviceTreeNode>,
+        container: &mut Vec<&'tree DeviceTreeNode<'a>>,
     ) -> bool {
         if index == slices.len() {
             return true;
@@ -160,67 +356,138 @@ impl DeviceTree {
                 return Self::find_along_path_internal(node, slices, index + 1, container);
             }
         }
-        return false;
+        false
+    }
+}
+
+/// A node of [DeviceTree] along with the nodes above it
+/// Dereferences to the node, so it can be used wherever a `&DeviceTreeNode` is expected
+#[derive(Clone)]
+pub struct NodeRef<'b, 'a> {
+    tree: &'b DeviceTree<'a>,
+    node: &'b DeviceTreeNode<'a>,
+    // shared with its siblings, so handing out children does not copy the way up
+    parent: Option<Rc<NodeRef<'b, 'a>>>,
+    depth: usize,
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Get the node itself
+    pub fn node(&self) -> &'b DeviceTreeNode<'a> {
+        self.node
+    }
+
+    /// Get the tree it belongs to
+    pub fn tree(&self) -> &'b DeviceTree<'a> {
+        self.tree
+    }
+
+    // the node declaring `phandle`, with its own ancestors
+    pub(crate) fn resolve(&self, phandle: u32) -> Result<NodeRef<'b, 'a>> {
+        self.tree.resolve(phandle)
+    }
+
+    fn child_of(parent: Rc<NodeRef<'b, 'a>>, node: &'b DeviceTreeNode<'a>) -> Self {
+        NodeRef {
+            tree: parent.tree,
+            node,
+            depth: parent.depth + 1,
+            parent: Some(parent),
+        }
+    }
+
+    /// Get its parent, `None` for the root
+    pub fn parent(&self) -> Option<NodeRef<'b, 'a>> {
+        self.parent.as_deref().cloned()
+    }
+
+    /// Iterate over the nodes above it, from its parent up to the root
+    pub fn ancestors(&self) -> impl Iterator<Item = NodeRef<'b, 'a>> {
+        core::iter::successors(self.parent(), |node| node.parent())
+    }
+
+    /// Get the number of nodes above it, the root is at depth 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Get its full path, e.g. `/soc/uart@10000000`
+    pub fn path(&self) -> String {
+        if self.parent.is_none() {
+            return String::from("/");
+        }
+        let mut names = Vec::with_capacity(self.depth);
+        let mut node = self;
+        while let Some(parent) = node.parent.as_deref() {
+            names.push(node.node.name());
+            node = parent;
+        }
+        let mut path = String::new();
+        for name in names.iter().rev() {
+            path.push('/');
+            path.push_str(name);
+        }
+        path
+    }
+
+    /// Iterate over its children, each knowing its way up through this node
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'b, 'a>> {
+        let parent = Rc::new(self.clone());
+        self.node
+            .nodes()
+            .iter()
+            .map(move |node| NodeRef::child_of(parent.clone(), node))
+    }
+
+    /// Decode the value of its property `name`, `None` if it has no such property
+    /// A value which cannot be parsed fails with an error naming this node and the property
+    pub fn try_value(&self, name: &str) -> Result<Option<PropertyValue<'b>>> {
+        self.node
+            .find_prop(name)
+            .map(NodeProperty::try_value)
+            .transpose()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+}
+
+impl<'a> Deref for NodeRef<'_, 'a> {
+    type Target = DeviceTreeNode<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.node
     }
 }
 
 /// Iterator for all the tree nodes
-pub struct DeviceTreeNodeIter<'a> {
-    queue: VecDeque<&'a DeviceTreeNode>,
+pub struct DeviceTreeNodeIter<'b, 'a> {
+    queue: VecDeque<NodeRef<'b, 'a>>,
 }
 
-impl<'a> Iterator for DeviceTreeNodeIter<'a> {
-    type Item = &'a DeviceTreeNode;
+impl<'b, 'a> Iterator for DeviceTreeNodeIter<'b, 'a> {
+    type Item = NodeRef<'b, 'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let res = self.queue.pop_front();
-        match res {
-            Some(node) if node.has_children() => {
-                for i in node.nodes() {
-                    self.queue.push_back(i);
-                }
-            }
-            _ => {}
+        if let Some(node) = &res {
+            self.queue.extend(node.children());
         }
         res
     }
 }
 
-impl Display for DeviceTree {
+impl Display for DeviceTree<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "{}", self.root)
     }
 }
 
-impl<'a> IntoIterator for &'a DeviceTree {
-    type Item = &'a DeviceTreeNode;
-    type IntoIter = DeviceTreeNodeIter<'a>;
+impl<'b, 'a> IntoIterator for &'b DeviceTree<'a> {
+    type Item = NodeRef<'b, 'a>;
+    type IntoIter = DeviceTreeNodeIter<'b, 'a>;
 
     fn into_iter(self) -> Self::IntoIter {
         DeviceTreeNodeIter {
-            queue: VecDeque::from([self.root()]),
-        }
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct InheritedValues(Vec<(String, u64)>);
-
-impl InheritedValues {
-    pub const fn new() -> Self {
-        InheritedValues(vec![])
-    }
-
-    pub fn find(&self, name: &str) -> Option<u64> {
-        for i in &self.0 {
-            if i.0.as_str() == name {
-                return Some(i.1);
-            }
+            queue: VecDeque::from([self.root_ref()]),
         }
-        None
-    }
-
-    pub fn insert(&mut self, name: String, value: u64) {
-        self.0.push((name, value));
     }
 }
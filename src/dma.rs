@@ -0,0 +1,102 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::node::DeviceTreeNode;
+use crate::traits::HasNamedProperty;
+use crate::translate::{map_range, range_rows, strip_flags, translate_up};
+
+/// A range of bus addresses a device can reach by DMA, and where it lands in CPU physical memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DmaWindow {
+    /// The first address as seen by the device
+    pub dma_start: u64,
+    /// The CPU physical address `dma_start` maps to
+    pub cpu_start: u64,
+    /// Its length in bytes
+    pub size: u64,
+}
+
+impl DmaWindow {
+    /// Get the last address the device can reach through this window
+    pub fn dma_limit(&self) -> u64 {
+        self.dma_start.saturating_add(self.size.saturating_sub(1))
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Translate an address used by `node` for DMA into a CPU physical address
+    /// Follows the `dma-ranges` of every bus up to the root, a missing or empty one maps one to one
+    pub fn dma_to_cpu(&self, node: &DeviceTreeNode<'a>, dma_address: u64) -> Option<u64> {
+        let address = match self.node_ref(node)?.parent() {
+            Some(bus) => translate_up(bus, dma_address as u128, "dma-ranges", false)?,
+            None => dma_address as u128,
+        };
+        u64::try_from(address).ok()
+    }
+
+    /// Translate a CPU physical address into the address `node` has to use for DMA
+    /// Returns `None` if the address is outside the `dma-ranges` of a bus on the way
+    pub fn cpu_to_dma(&self, node: &DeviceTreeNode<'a>, address: u64) -> Option<u64> {
+        let buses = self.node_ref(node)?.ancestors().collect::<Vec<_>>();
+        let mut address = address as u128;
+        for pair in buses.windows(2).rev() {
+            let (bus, parent) = (&pair[0], &pair[1]);
+            if has_dma_ranges(bus) {
+                address = map_range(bus, parent, "dma-ranges", address, false)?;
+            }
+        }
+        u64::try_from(address).ok()
+    }
+
+    /// Whether DMA of `node` is coherent with the CPU caches
+    /// The nearest `dma-coherent` or `dma-noncoherent` on it or its parents decides, otherwise it is not
+    pub fn dma_coherent(&self, node: &DeviceTreeNode<'a>) -> bool {
+        let Some(node) = self.node_ref(node) else {
+            return false;
+        };
+        for node in core::iter::once(node.clone()).chain(node.ancestors()) {
+            if node.find_prop("dma-coherent").is_some() {
+                return true;
+            }
+            if node.find_prop("dma-noncoherent").is_some() {
+                return false;
+            }
+        }
+        false
+    }
+
+    /// Get the windows `node` can reach by DMA, from the nearest bus with a non-empty `dma-ranges`
+    /// An empty list means DMA is not limited by the tree
+    /// Returns `None` if the `dma-ranges` cannot be read or translated
+    pub fn dma_windows(&self, node: &DeviceTreeNode<'a>) -> Option<Vec<DmaWindow>> {
+        let node = self.node_ref(node)?;
+        for bus in node.ancestors() {
+            let Some(parent) = bus.parent() else {
+                break;
+            };
+            if !has_dma_ranges(&bus) {
+                continue;
+            }
+            return range_rows(&bus, &parent, "dma-ranges")?
+                .into_iter()
+                .map(|(child, cpu, size)| {
+                    let cpu = translate_up(parent.clone(), cpu, "dma-ranges", false)?;
+                    Some(DmaWindow {
+                        dma_start: u64::try_from(strip_flags(&bus, child)).ok()?,
+                        cpu_start: u64::try_from(cpu).ok()?,
+                        size: u64::try_from(size).ok()?,
+                    })
+                })
+                .collect();
+        }
+        Some(Vec::new())
+    }
+}
+
+fn has_dma_ranges(bus: &NodeRef) -> bool {
+    bus.find_prop("dma-ranges")
+        .is_some_and(|prop| !prop.raw().is_empty())
+}
@@ -0,0 +1,865 @@
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{collections::BTreeSet, string::String, vec::Vec};
+
+use crate::device_tree::DeviceTree;
+use crate::error::ErrorKind::{IncludeNotFound, InvalidSyntax, UnresolvedReference};
+use crate::error::Result;
+use crate::node::{DeviceTreeNode, MAX_DEPTH};
+use crate::reservation::MemoryReservation;
+
+const MAX_INCLUDE_DEPTH: usize = 32;
+// nesting of unary operators, parentheses and conditionals before the source is rejected
+const MAX_EXPR_DEPTH: usize = 256;
+
+impl DeviceTree<'static> {
+    /// Parses device tree source and constructs [DeviceTree] the same way dtc compiles it
+    /// `/include/` is rejected since there is nowhere to read the files from
+    pub fn from_dts(source: &str) -> Result<Self> {
+        Self::from_dts_with(source, |_| None)
+    }
+
+    /// Parses device tree source and constructs [DeviceTree] the same way dtc compiles it
+    /// The contents of the files named by `/include/` are asked from `include`
+    pub fn from_dts_with<F>(source: &str, mut include: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut expanded = Vec::<u8>::new();
+        expand_includes(source.as_bytes(), &mut include, 0, &mut expanded)?;
+        let mut parser = Parser {
+            src: &expanded,
+            pos: 0,
+            depth: 0,
+            expr_depth: 0,
+        };
+        let (reservations, mut root) = parser.parse_file().map_err(|err| err.at(parser.pos))?;
+        resolve_references(&mut root)?;
+        Ok(DeviceTree::from_parts(reservations, root.into_node()))
+    }
+}
+
+// `/include/` is textual, so the files are pasted in before anything is parsed
+fn expand_includes<F>(src: &[u8], include: &mut F, depth: usize, out: &mut Vec<u8>) -> Result<()>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(IncludeNotFound.into());
+    }
+    let mut pos = 0;
+    while pos < src.len() {
+        let rest = &src[pos..];
+        let skipped = if rest.starts_with(b"//") {
+            rest.iter().position(|c| *c == b'\n').unwrap_or(rest.len())
+        } else if rest.starts_with(b"/*") {
+            find(rest, b"*/").map(|end| end + 2).ok_or(InvalidSyntax)?
+        } else if rest[0] == b'"' {
+            string_literal_len(rest)?
+        } else if rest.starts_with(b"/include/") {
+            let mut end = "/include/".len();
+            while rest.get(end).is_some_and(|c| c.is_ascii_whitespace()) {
+                end += 1;
+            }
+            let len = string_literal_len(rest.get(end..).ok_or(InvalidSyntax)?)?;
+            let name =
+                core::str::from_utf8(&rest[end + 1..end + len - 1]).map_err(|_| InvalidSyntax)?;
+            let content = include(name).ok_or(IncludeNotFound)?;
+            expand_includes(content.as_bytes(), include, depth + 1, out)?;
+            pos += end + len;
+            continue;
+        } else {
+            1
+        };
+        out.extend_from_slice(&rest[..skipped]);
+        pos += skipped;
+    }
+    Ok(())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// length of a quoted string including both quotes
+fn string_literal_len(src: &[u8]) -> Result<usize> {
+    if src.first() != Some(&b'"') {
+        return Err(InvalidSyntax.into());
+    }
+    let mut end = 1;
+    loop {
+        match src.get(end) {
+            Some(b'"') => return Ok(end + 1),
+            Some(b'\\') => end += 2,
+            Some(_) => end += 1,
+            None => return Err(InvalidSyntax.into()),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Reference {
+    Label(String),
+    Path(String),
+}
+
+enum Chunk {
+    Bytes(Vec<u8>),
+    PHandle(Reference),
+    Path(Reference),
+}
+
+struct SourceProperty {
+    name: String,
+    value: Vec<Chunk>,
+}
+
+impl SourceProperty {
+    // only meaningful once the references have been resolved
+    fn raw(&self) -> Vec<u8> {
+        let mut raw = Vec::<u8>::new();
+        for chunk in &self.value {
+            if let Chunk::Bytes(bytes) = chunk {
+                raw.extend_from_slice(bytes);
+            }
+        }
+        raw
+    }
+}
+
+#[derive(Default)]
+struct SourceNode {
+    name: String,
+    labels: Vec<String>,
+    props: Vec<SourceProperty>,
+    nodes: Vec<SourceNode>,
+}
+
+impl SourceNode {
+    fn named(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+
+    fn set_prop(&mut self, prop: SourceProperty) {
+        match self.props.iter_mut().find(|p| p.name == prop.name) {
+            Some(existing) => existing.value = prop.value,
+            None => self.props.push(prop),
+        }
+    }
+
+    fn phandle(&self) -> Option<u32> {
+        self.props
+            .iter()
+            .find(|p| p.name == "phandle" || p.name == "linux,phandle")
+            .and_then(|p| <[u8; 4]>::try_from(p.raw().as_slice()).ok())
+            .map(u32::from_be_bytes)
+    }
+
+    fn find_label_mut(&mut self, label: &str) -> Option<&mut SourceNode> {
+        if self.labels.iter().any(|l| l == label) {
+            return Some(self);
+        }
+        self.nodes
+            .iter_mut()
+            .find_map(|node| node.find_label_mut(label))
+    }
+
+    fn find_path_mut(&mut self, path: &str) -> Option<&mut SourceNode> {
+        let mut node = self;
+        for name in path.split('/').filter(|s| !s.is_empty()) {
+            node = node.nodes.iter_mut().find(|n| n.name == name)?;
+        }
+        Some(node)
+    }
+
+    fn find_mut(&mut self, reference: &Reference) -> Option<&mut SourceNode> {
+        match reference {
+            Reference::Label(label) => self.find_label_mut(label),
+            Reference::Path(path) => self.find_path_mut(path),
+        }
+    }
+
+    // child indices leading from this node to the referenced one
+    fn locate(&self, reference: &Reference) -> Option<Vec<usize>> {
+        match reference {
+            Reference::Label(label) => {
+                if self.labels.iter().any(|l| l == label) {
+                    return Some(Vec::new());
+                }
+                self.nodes.iter().enumerate().find_map(|(i, node)| {
+                    let mut indices = node.locate(reference)?;
+                    indices.insert(0, i);
+                    Some(indices)
+                })
+            }
+            Reference::Path(path) => {
+                let mut node = self;
+                let mut indices = Vec::new();
+                for name in path.split('/').filter(|s| !s.is_empty()) {
+                    let index = node.nodes.iter().position(|n| n.name == name)?;
+                    indices.push(index);
+                    node = &node.nodes[index];
+                }
+                Some(indices)
+            }
+        }
+    }
+
+    fn at(&self, indices: &[usize]) -> &SourceNode {
+        indices.iter().fold(self, |node, i| &node.nodes[*i])
+    }
+
+    fn at_mut(&mut self, indices: &[usize]) -> &mut SourceNode {
+        indices.iter().fold(self, |node, i| &mut node.nodes[*i])
+    }
+
+    fn path_of(&self, indices: &[usize]) -> String {
+        let mut path = String::new();
+        let mut node = self;
+        for i in indices {
+            node = &node.nodes[*i];
+            path.push('/');
+            path.push_str(&node.name);
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        path
+    }
+
+    fn remove(&mut self, reference: &Reference) -> bool {
+        match self
+            .locate(reference)
+            .as_deref()
+            .and_then(|i| i.split_last())
+        {
+            Some((last, parent)) => {
+                self.at_mut(parent).nodes.remove(*last);
+                true
+            }
+            // the root cannot be deleted
+            None => false,
+        }
+    }
+
+    fn into_node(self) -> DeviceTreeNode<'static> {
+        let mut node = DeviceTreeNode::new(self.name);
+        for prop in self.props {
+            let raw = prop.raw();
+            node.set_prop_raw(prop.name, raw);
+        }
+        for child in self.nodes {
+            node.add_child(child.into_node());
+        }
+        node
+    }
+}
+
+// phandles are handed out like dtc does: in the order the references appear,
+// to the lowest value not taken yet
+fn resolve_references(root: &mut SourceNode) -> Result<()> {
+    let mut used = BTreeSet::<u32>::new();
+    let mut chunks = Vec::<(Vec<usize>, usize, usize)>::new();
+    collect_references(root, &mut Vec::new(), &mut used, &mut chunks);
+
+    let mut next = 1u32;
+    for (indices, prop, chunk) in chunks {
+        let owner = root.at(&indices);
+        let (reference, is_phandle) = match &owner.props[prop].value[chunk] {
+            Chunk::PHandle(reference) => (reference.clone(), true),
+            Chunk::Path(reference) => (reference.clone(), false),
+            Chunk::Bytes(_) => continue,
+        };
+        let target = root.locate(&reference).ok_or(UnresolvedReference)?;
+        let bytes = if is_phandle {
+            let phandle = match root.at(&target).phandle() {
+                Some(phandle) => phandle,
+                None => {
+                    while used.contains(&next) {
+                        next += 1;
+                    }
+                    used.insert(next);
+                    root.at_mut(&target).set_prop(SourceProperty {
+                        name: "phandle".into(),
+                        value: Vec::from([Chunk::Bytes(next.to_be_bytes().to_vec())]),
+                    });
+                    next
+                }
+            };
+            phandle.to_be_bytes().to_vec()
+        } else {
+            let mut path = root.path_of(&target).into_bytes();
+            path.push(0);
+            path
+        };
+        root.at_mut(&indices).props[prop].value[chunk] = Chunk::Bytes(bytes);
+    }
+    Ok(())
+}
+
+fn collect_references(
+    node: &SourceNode,
+    indices: &mut Vec<usize>,
+    used: &mut BTreeSet<u32>,
+    chunks: &mut Vec<(Vec<usize>, usize, usize)>,
+) {
+    if let Some(phandle) = node.phandle() {
+        used.insert(phandle);
+    }
+    for (p, prop) in node.props.iter().enumerate() {
+        for (c, chunk) in prop.value.iter().enumerate() {
+            if !matches!(chunk, Chunk::Bytes(_)) {
+                chunks.push((indices.clone(), p, c));
+            }
+        }
+    }
+    for (i, child) in node.nodes.iter().enumerate() {
+        indices.push(i);
+        collect_references(child, indices, used, chunks);
+        indices.pop();
+    }
+}
+
+struct Parser<'s> {
+    src: &'s [u8],
+    pos: usize,
+    // nesting of the node bodies, as deep as a blob may be
+    depth: usize,
+    expr_depth: usize,
+}
+
+impl Parser<'_> {
+    fn parse_file(&mut self) -> Result<(Vec<MemoryReservation>, SourceNode)> {
+        self.expect("/dts-v1/")?;
+        self.expect(";")?;
+        let mut reservations = Vec::<MemoryReservation>::new();
+        loop {
+            let start = self.pos;
+            self.parse_labels()?;
+            if self.eat("/memreserve/") {
+                let address = self.parse_primary()?;
+                let size = self.parse_primary()?;
+                self.expect(";")?;
+                reservations.push(MemoryReservation::new(address, size));
+            } else {
+                self.pos = start;
+                break;
+            }
+        }
+
+        let mut root = SourceNode::default();
+        let mut has_root = false;
+        loop {
+            self.skip_ws();
+            if self.pos >= self.src.len() {
+                break;
+            }
+            let labels = self.parse_labels()?;
+            if self.eat("/delete-node/") {
+                let reference = self.parse_reference()?;
+                self.expect(";")?;
+                if !root.remove(&reference) {
+                    return Err(UnresolvedReference.into());
+                }
+            } else if self.eat("/") {
+                root.labels.extend(labels);
+                self.parse_body(&mut root)?;
+                has_root = true;
+            } else if self.peek() == Some(b'&') && has_root {
+                let reference = self.parse_reference()?;
+                let node = root.find_mut(&reference).ok_or(UnresolvedReference)?;
+                node.labels.extend(labels);
+                self.parse_body(node)?;
+            } else {
+                return Err(InvalidSyntax.into());
+            }
+        }
+        if has_root {
+            Ok((reservations, root))
+        } else {
+            Err(InvalidSyntax.into())
+        }
+    }
+
+    // `{ ... };` applied onto `node`, which merges into what it already holds
+    fn parse_body(&mut self, node: &mut SourceNode) -> Result<()> {
+        if self.depth > MAX_DEPTH {
+            return Err(InvalidSyntax.into());
+        }
+        self.expect("{")?;
+        loop {
+            if self.eat("}") {
+                return self.expect(";");
+            }
+            if self.eat("/delete-property/") {
+                let name = self.parse_name()?;
+                self.expect(";")?;
+                node.props.retain(|p| p.name != name);
+                continue;
+            }
+            if self.eat("/delete-node/") {
+                let name = self.parse_name()?;
+                self.expect(";")?;
+                node.nodes.retain(|n| n.name != name);
+                continue;
+            }
+            let labels = self.parse_labels()?;
+            let name = self.parse_name()?;
+            self.skip_ws();
+            match self.peek() {
+                Some(b'{') => {
+                    let index = match node.nodes.iter().position(|n| n.name == name) {
+                        Some(index) => index,
+                        None => {
+                            node.nodes.push(SourceNode::named(name));
+                            node.nodes.len() - 1
+                        }
+                    };
+                    let child = &mut node.nodes[index];
+                    child.labels.extend(labels);
+                    self.depth += 1;
+                    let res = self.parse_body(child);
+                    self.depth -= 1;
+                    res?;
+                }
+                Some(b'=') => {
+                    self.pos += 1;
+                    let value = self.parse_values()?;
+                    self.expect(";")?;
+                    node.set_prop(SourceProperty { name, value });
+                }
+                Some(b';') => {
+                    self.pos += 1;
+                    node.set_prop(SourceProperty {
+                        name,
+                        value: Vec::new(),
+                    });
+                }
+                _ => return Err(InvalidSyntax.into()),
+            }
+        }
+    }
+
+    // comma separated strings, cells, byte strings and path references
+    fn parse_values(&mut self) -> Result<Vec<Chunk>> {
+        let mut value = Vec::<Chunk>::new();
+        loop {
+            self.parse_labels()?;
+            self.skip_ws();
+            match self.peek() {
+                Some(b'"') => {
+                    let mut bytes = self.parse_string()?;
+                    bytes.push(0);
+                    value.push(Chunk::Bytes(bytes));
+                }
+                Some(b'[') => value.push(Chunk::Bytes(self.parse_byte_string()?)),
+                Some(b'&') => value.push(Chunk::Path(self.parse_reference()?)),
+                _ => {
+                    let bits = if self.eat("/bits/") {
+                        self.parse_primary()? as usize
+                    } else {
+                        32
+                    };
+                    if !matches!(bits, 8 | 16 | 32 | 64) {
+                        return Err(InvalidSyntax.into());
+                    }
+                    self.parse_cells(bits, &mut value)?;
+                }
+            }
+            self.parse_labels()?;
+            if !self.eat(",") {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_cells(&mut self, bits: usize, value: &mut Vec<Chunk>) -> Result<()> {
+        self.expect("<")?;
+        let mut bytes = Vec::<u8>::new();
+        loop {
+            self.parse_labels()?;
+            if self.eat(">") {
+                break;
+            }
+            if self.peek() == Some(b'&') {
+                if bits != 32 {
+                    return Err(InvalidSyntax.into());
+                }
+                let reference = self.parse_reference()?;
+                value.push(Chunk::Bytes(core::mem::take(&mut bytes)));
+                value.push(Chunk::PHandle(reference));
+                continue;
+            }
+            let cell = self.parse_primary()?;
+            if bits < 64 {
+                // anything above the mask must be a sign extension
+                let mask = (1u64 << bits) - 1;
+                if cell > mask && cell | mask != u64::MAX {
+                    return Err(InvalidSyntax.into());
+                }
+            }
+            bytes.extend_from_slice(&cell.to_be_bytes()[8 - bits / 8..]);
+        }
+        value.push(Chunk::Bytes(bytes));
+        Ok(())
+    }
+
+    fn parse_byte_string(&mut self) -> Result<Vec<u8>> {
+        self.expect("[")?;
+        let mut bytes = Vec::<u8>::new();
+        loop {
+            self.parse_labels()?;
+            if self.eat("]") {
+                return Ok(bytes);
+            }
+            let high = self.next_hex_digit()?;
+            let low = self.next_hex_digit()?;
+            bytes.push(high << 4 | low);
+        }
+    }
+
+    fn next_hex_digit(&mut self) -> Result<u8> {
+        let digit = self
+            .peek()
+            .and_then(|c| (c as char).to_digit(16))
+            .ok_or(InvalidSyntax)?;
+        self.pos += 1;
+        Ok(digit as u8)
+    }
+
+    fn parse_string(&mut self) -> Result<Vec<u8>> {
+        self.expect("\"")?;
+        let mut bytes = Vec::<u8>::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(bytes);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    bytes.push(self.parse_escape()?);
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    bytes.push(c);
+                }
+                None => return Err(InvalidSyntax.into()),
+            }
+        }
+    }
+
+    // the character after a backslash
+    fn parse_escape(&mut self) -> Result<u8> {
+        let c = self.peek().ok_or(InvalidSyntax)?;
+        self.pos += 1;
+        let escaped = match c {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            b'x' => {
+                let start = self.pos;
+                while self.pos - start < 2 && self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                    self.pos += 1;
+                }
+                self.radix_value(start, 16)?
+            }
+            b'0'..=b'7' => {
+                let start = self.pos - 1;
+                while self.pos - start < 3 && self.peek().is_some_and(|c| matches!(c, b'0'..=b'7'))
+                {
+                    self.pos += 1;
+                }
+                self.radix_value(start, 8)?
+            }
+            other => other,
+        };
+        Ok(escaped)
+    }
+
+    fn radix_value(&self, start: usize, radix: u32) -> Result<u8> {
+        let digits = core::str::from_utf8(&self.src[start..self.pos]).map_err(|_| InvalidSyntax)?;
+        u32::from_str_radix(digits, radix)
+            .ok()
+            .and_then(|v| u8::try_from(v).ok())
+            .ok_or(InvalidSyntax.into())
+    }
+
+    fn parse_reference(&mut self) -> Result<Reference> {
+        self.expect("&")?;
+        if self.peek() == Some(b'{') {
+            self.pos += 1;
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != b'}') {
+                self.pos += 1;
+            }
+            let path = self.text(start)?;
+            self.expect("}")?;
+            Ok(Reference::Path(path))
+        } else {
+            let start = self.pos;
+            while self.peek().is_some_and(is_label_char) {
+                self.pos += 1;
+            }
+            let label = self.text(start)?;
+            if label.is_empty() {
+                Err(InvalidSyntax.into())
+            } else {
+                Ok(Reference::Label(label))
+            }
+        }
+    }
+
+    // any number of `label:` in front of something
+    fn parse_labels(&mut self) -> Result<Vec<String>> {
+        let mut labels = Vec::<String>::new();
+        loop {
+            self.skip_ws();
+            let start = self.pos;
+            if !self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+            {
+                return Ok(labels);
+            }
+            while self.peek().is_some_and(is_label_char) {
+                self.pos += 1;
+            }
+            if self.peek() == Some(b':') {
+                let label = self.text(start)?;
+                self.pos += 1;
+                labels.push(label);
+            } else {
+                self.pos = start;
+                return Ok(labels);
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.peek().is_some_and(is_name_char) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            Err(InvalidSyntax.into())
+        } else {
+            self.text(start)
+        }
+    }
+
+    // a literal, a character or a parenthesized expression
+    fn parse_primary(&mut self) -> Result<u64> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.nested(Self::parse_expr)?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Some(b'\'') => {
+                self.pos += 1;
+                let value = match self.peek() {
+                    Some(b'\\') => {
+                        self.pos += 1;
+                        self.parse_escape()?
+                    }
+                    Some(c) => {
+                        self.pos += 1;
+                        c
+                    }
+                    None => return Err(InvalidSyntax.into()),
+                };
+                if self.peek() != Some(b'\'') {
+                    return Err(InvalidSyntax.into());
+                }
+                self.pos += 1;
+                Ok(value as u64)
+            }
+            Some(b'0'..=b'9') => {
+                let (radix, start) = if self.src[self.pos..].starts_with(b"0x")
+                    || self.src[self.pos..].starts_with(b"0X")
+                {
+                    (16, self.pos + 2)
+                } else if self.peek() == Some(b'0') {
+                    (8, self.pos)
+                } else {
+                    (10, self.pos)
+                };
+                self.pos = start;
+                while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+                    self.pos += 1;
+                }
+                let literal = self.text(start)?;
+                let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+                u64::from_str_radix(digits, radix).map_err(|_| InvalidSyntax.into())
+            }
+            _ => Err(InvalidSyntax.into()),
+        }
+    }
+
+    // parses one level deeper into an expression, failing past `MAX_EXPR_DEPTH`
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<u64>) -> Result<u64> {
+        if self.expr_depth >= MAX_EXPR_DEPTH {
+            return Err(InvalidSyntax.into());
+        }
+        self.expr_depth += 1;
+        let res = parse(self);
+        self.expr_depth -= 1;
+        res
+    }
+
+    fn parse_expr(&mut self) -> Result<u64> {
+        let condition = self.parse_binary(0)?;
+        if self.eat("?") {
+            let yes = self.nested(Self::parse_expr)?;
+            self.expect(":")?;
+            let no = self.nested(Self::parse_expr)?;
+            Ok(if condition != 0 { yes } else { no })
+        } else {
+            Ok(condition)
+        }
+    }
+
+    // precedence climbing over the C binary operators
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<u64> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            self.skip_ws();
+            let (op, precedence) = match self.peek_operator() {
+                Some((op, precedence)) if precedence >= min_precedence => (op, precedence),
+                _ => return Ok(lhs),
+            };
+            self.pos += op.len();
+            let rhs = self.parse_binary(precedence + 1)?;
+            lhs = match op {
+                "||" => (lhs != 0 || rhs != 0) as u64,
+                "&&" => (lhs != 0 && rhs != 0) as u64,
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "==" => (lhs == rhs) as u64,
+                "!=" => (lhs != rhs) as u64,
+                "<" => (lhs < rhs) as u64,
+                ">" => (lhs > rhs) as u64,
+                "<=" => (lhs <= rhs) as u64,
+                ">=" => (lhs >= rhs) as u64,
+                "<<" => lhs.checked_shl(rhs as u32).unwrap_or(0),
+                ">>" => lhs.checked_shr(rhs as u32).unwrap_or(0),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                "/" => lhs.checked_div(rhs).ok_or(InvalidSyntax)?,
+                _ => lhs.checked_rem(rhs).ok_or(InvalidSyntax)?,
+            };
+        }
+    }
+
+    fn peek_operator(&self) -> Option<(&'static str, u8)> {
+        const OPERATORS: [(&str, u8); 18] = [
+            ("||", 1),
+            ("&&", 2),
+            ("==", 6),
+            ("!=", 6),
+            ("<=", 7),
+            (">=", 7),
+            ("<<", 8),
+            (">>", 8),
+            ("|", 3),
+            ("^", 4),
+            ("&", 5),
+            ("<", 7),
+            (">", 7),
+            ("+", 9),
+            ("-", 9),
+            ("*", 10),
+            ("/", 10),
+            ("%", 10),
+        ];
+        let rest = &self.src[self.pos..];
+        OPERATORS
+            .into_iter()
+            .find(|(op, _)| rest.starts_with(op.as_bytes()))
+    }
+
+    fn parse_unary(&mut self) -> Result<u64> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(self.nested(Self::parse_unary)?.wrapping_neg())
+            }
+            Some(b'~') => {
+                self.pos += 1;
+                Ok(!self.nested(Self::parse_unary)?)
+            }
+            Some(b'!') => {
+                self.pos += 1;
+                Ok((self.nested(Self::parse_unary)? == 0) as u64)
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn text(&self, start: usize) -> Result<String> {
+        core::str::from_utf8(&self.src[start..self.pos])
+            .map(String::from)
+            .map_err(|_| InvalidSyntax.into())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        loop {
+            let rest = &self.src[self.pos.min(self.src.len())..];
+            if rest.first().is_some_and(|c| c.is_ascii_whitespace()) {
+                self.pos += 1;
+            } else if rest.starts_with(b"//") {
+                self.pos += rest.iter().position(|c| *c == b'\n').unwrap_or(rest.len());
+            } else if rest.starts_with(b"/*") {
+                // unterminated comments are rejected while expanding includes
+                self.pos += find(rest, b"*/").map_or(rest.len(), |end| end + 2);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        if self.src[self.pos..].starts_with(token.as_bytes()) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(InvalidSyntax.into())
+        }
+    }
+}
+
+fn is_label_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+fn is_name_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b",._+*#?@-".contains(&c)
+}
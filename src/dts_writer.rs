@@ -0,0 +1,321 @@
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+#[cfg(not(feature = "std"))]
+use core::fmt::Write;
+#[cfg(feature = "std")]
+use std::fmt::Write;
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::device_tree::DeviceTree;
+use crate::node::DeviceTreeNode;
+use crate::prop::{NodeProperty, PropertyValue};
+use crate::traits::HasNamedProperty;
+
+impl DeviceTree<'_> {
+    /// Writes the tree as device tree source which compiles back to the same blob
+    ///
+    /// Unlike [core::fmt::Display], values are printed as they are stored: strings when they
+    /// look like strings, otherwise cells or bytes. Labels are recovered from `__symbols__`,
+    /// and the phandles of `*-parent` and of the known phandle lists, such as `clocks` or
+    /// `*-gpios`, are written as `&label` when they point at a labeled node
+    pub fn to_dts(&self) -> String {
+        let mut labels = BTreeMap::<String, &str>::new();
+        if let Some(symbols) = self
+            .root()
+            .nodes()
+            .iter()
+            .find(|n| n.name() == "__symbols__")
+        {
+            for prop in symbols.props() {
+                if let (true, PropertyValue::String(path)) = (is_label(prop.name()), prop.value()) {
+                    labels.entry(path.into()).or_insert(prop.name());
+                }
+            }
+        }
+        let mut phandles = BTreeMap::new();
+        collect_phandles(self.root(), &mut String::new(), &mut phandles);
+
+        let context = Context { labels, phandles };
+        let mut dts = String::from("/dts-v1/;\n\n");
+        for reservation in self.memory_reservations() {
+            // writing to a String never fails
+            let _ = writeln!(
+                dts,
+                "/memreserve/ {:#018x} {:#018x};",
+                reservation.address(),
+                reservation.size()
+            );
+        }
+        if self.memory_reservations().next().is_some() {
+            dts.push('\n');
+        }
+        context.write_node(&mut dts, self.root(), &mut String::new(), 0);
+        dts
+    }
+}
+
+struct Context<'t, 'a> {
+    // node path and its label
+    labels: BTreeMap<String, &'t str>,
+    // phandle and the path of the node it belongs to, along with the node
+    phandles: BTreeMap<u32, (String, &'t DeviceTreeNode<'a>)>,
+}
+
+impl<'a> Context<'_, 'a> {
+    fn label_of(&self, path: &str) -> Option<&str> {
+        self.labels.get(path).copied()
+    }
+
+    fn label_of_phandle(&self, phandle: u32) -> Option<&str> {
+        let (path, _) = self.phandles.get(&phandle)?;
+        self.label_of(path)
+    }
+
+    fn write_node(
+        &self,
+        dts: &mut String,
+        node: &DeviceTreeNode<'a>,
+        path: &mut String,
+        depth: usize,
+    ) {
+        let parent_len = path.len();
+        if depth > 0 {
+            push_child_path(path, node.name());
+        }
+        indent(dts, depth);
+        let full_path = if path.is_empty() { "/" } else { path.as_str() };
+        if let Some(label) = self.label_of(full_path) {
+            dts.push_str(label);
+            dts.push_str(": ");
+        }
+        dts.push_str(if depth == 0 { "/" } else { node.name() });
+        dts.push_str(" {\n");
+        for prop in node.props() {
+            indent(dts, depth + 1);
+            self.write_prop(dts, node, prop);
+        }
+        for child in node.nodes() {
+            dts.push('\n');
+            self.write_node(dts, child, path, depth + 1);
+        }
+        indent(dts, depth);
+        dts.push_str("};\n");
+        path.truncate(parent_len);
+    }
+
+    // the `(phandle, args)` entries of a known phandle list, `None` if it is not one or does not decode
+    fn phandle_list(
+        &self,
+        node: &DeviceTreeNode<'a>,
+        prop: &NodeProperty,
+    ) -> Option<Vec<(u32, Vec<u32>)>> {
+        // the `gpios` of a hog are bare specifiers of its parent
+        if node.find_prop("gpio-hog").is_some() {
+            return None;
+        }
+        match phandle_list_cells(prop.name())? {
+            Some(cells_name) => {
+                // providers come from the table, going through the tree for each would be quadratic
+                let cells = prop.cells().ok()?;
+                let mut entries = Vec::new();
+                let mut position = 0;
+                while let Some(&phandle) = cells.get(position) {
+                    let count = match phandle {
+                        0 => 0,
+                        phandle => {
+                            let (_, provider) = self.phandles.get(&phandle)?;
+                            provider.prop_u32(cells_name)? as usize
+                        }
+                    };
+                    let args = cells.get(position + 1..)?.get(..count)?;
+                    entries.push((phandle, args.to_vec()));
+                    position += 1 + count;
+                }
+                Some(entries)
+            }
+            None => Some(
+                prop.cells()
+                    .ok()?
+                    .into_iter()
+                    .map(|phandle| (phandle, Vec::new()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn write_prop(&self, dts: &mut String, node: &DeviceTreeNode<'a>, prop: &NodeProperty) {
+        let raw = prop.raw();
+        dts.push_str(prop.name());
+        if raw.is_empty() {
+            dts.push_str(";\n");
+            return;
+        }
+        dts.push_str(" = ");
+        if is_string_list(raw) {
+            for (i, s) in raw[..raw.len() - 1].split(|c| *c == 0).enumerate() {
+                if i > 0 {
+                    dts.push_str(", ");
+                }
+                write_string(dts, s);
+            }
+        } else if let Some(entries) = self.phandle_list(node, prop) {
+            dts.push('<');
+            for (i, (phandle, args)) in entries.iter().enumerate() {
+                if i > 0 {
+                    dts.push(' ');
+                }
+                match self.label_of_phandle(*phandle) {
+                    Some(label) => {
+                        dts.push('&');
+                        dts.push_str(label);
+                    }
+                    None => write_cell(dts, *phandle),
+                }
+                for arg in args {
+                    dts.push(' ');
+                    write_cell(dts, *arg);
+                }
+            }
+            dts.push('>');
+        } else if raw.len() % 4 == 0 {
+            let phandle = match prop.value() {
+                PropertyValue::PHandle(phandle) => self.label_of_phandle(phandle),
+                _ => None,
+            };
+            dts.push('<');
+            match phandle {
+                Some(label) => {
+                    dts.push('&');
+                    dts.push_str(label);
+                }
+                None => {
+                    for (i, cell) in raw.chunks_exact(4).enumerate() {
+                        if i > 0 {
+                            dts.push(' ');
+                        }
+                        write_cell(
+                            dts,
+                            u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]]),
+                        );
+                    }
+                }
+            }
+            dts.push('>');
+        } else {
+            dts.push('[');
+            for (i, byte) in raw.iter().enumerate() {
+                if i > 0 {
+                    dts.push(' ');
+                }
+                let _ = write!(dts, "{:02x}", byte);
+            }
+            dts.push(']');
+        }
+        dts.push_str(";\n");
+    }
+}
+
+// the first node claiming a phandle owns it, as when the tree resolves it
+fn collect_phandles<'t, 'a>(
+    node: &'t DeviceTreeNode<'a>,
+    path: &mut String,
+    phandles: &mut BTreeMap<u32, (String, &'t DeviceTreeNode<'a>)>,
+) {
+    for prop in node.props() {
+        if prop.name() == "phandle" || prop.name() == "linux,phandle" {
+            if let Ok(cell) = <[u8; 4]>::try_from(prop.raw()) {
+                let full_path = if path.is_empty() { "/" } else { path.as_str() };
+                phandles
+                    .entry(u32::from_be_bytes(cell))
+                    .or_insert_with(|| (full_path.into(), node));
+            }
+        }
+    }
+    for child in node.nodes() {
+        let parent_len = path.len();
+        push_child_path(path, child.name());
+        collect_phandles(child, path, phandles);
+        path.truncate(parent_len);
+    }
+}
+
+// phandle lists and the `#*-cells` property of their providers, `None` for bare phandles
+fn phandle_list_cells(name: &str) -> Option<Option<&'static str>> {
+    let cells_name = match name {
+        "clocks" | "assigned-clocks" => "#clock-cells",
+        "interrupts-extended" => "#interrupt-cells",
+        "phys" => "#phy-cells",
+        "resets" => "#reset-cells",
+        "power-domains" => "#power-domain-cells",
+        "dmas" => "#dma-cells",
+        "iommus" => "#iommu-cells",
+        "mboxes" => "#mbox-cells",
+        "pwms" => "#pwm-cells",
+        "io-channels" => "#io-channel-cells",
+        "memory-region" => return Some(None),
+        name if name == "gpios" || name.ends_with("-gpios") || name.ends_with("-gpio") => {
+            "#gpio-cells"
+        }
+        name if name
+            .strip_prefix("pinctrl-")
+            .is_some_and(|id| !id.is_empty() && id.bytes().all(|c| c.is_ascii_digit())) =>
+        {
+            return Some(None)
+        }
+        _ => return None,
+    };
+    Some(Some(cells_name))
+}
+
+fn write_cell(dts: &mut String, cell: u32) {
+    // writing to a String never fails
+    let _ = write!(dts, "{:#04x}", cell);
+}
+
+fn push_child_path(path: &mut String, name: &str) {
+    path.push('/');
+    path.push_str(name);
+}
+
+fn indent(dts: &mut String, depth: usize) {
+    for _ in 0..depth {
+        dts.push('\t');
+    }
+}
+
+// zero-terminated, non-empty pieces of printable characters
+fn is_string_list(raw: &[u8]) -> bool {
+    match raw.split_last() {
+        Some((0, body)) => {
+            !body.is_empty()
+                && body.split(|c| *c == 0).all(|s| !s.is_empty())
+                && body.iter().all(|c| {
+                    *c == 0 || c.is_ascii_graphic() || *c == b' ' || *c == b'\t' || *c == b'\n'
+                })
+        }
+        _ => false,
+    }
+}
+
+fn write_string(dts: &mut String, s: &[u8]) {
+    dts.push('"');
+    for c in s {
+        match c {
+            b'"' => dts.push_str("\\\""),
+            b'\\' => dts.push_str("\\\\"),
+            b'\t' => dts.push_str("\\t"),
+            b'\n' => dts.push_str("\\n"),
+            c => dts.push(*c as char),
+        }
+    }
+    dts.push('"');
+}
+
+fn is_label(name: &str) -> bool {
+    let mut chars = name.bytes();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == b'_')
+}
@@ -1,9 +1,18 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::string::String;
+
 /// Result for [crate::device_tree::DeviceTree] parsing
 pub type Result<T> = core::result::Result<T, DeviceTreeError>;
 
-/// Errors for [crate::device_tree::DeviceTree] parsing
-#[derive(Debug, Copy, Clone)]
-pub enum DeviceTreeError {
+/// What went wrong, the cause of a [DeviceTreeError]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
     /// Wrong magic number
     InvalidMagicNumber,
     /// Data are too short for parsing
@@ -14,6 +23,147 @@ pub enum DeviceTreeError {
     ParsingFailed,
     /// Memory cannot be accessed
     MemoryAccessFailed,
-    /// Missing definition of the address or size length
+    /// Missing definition of the address, size or specifier length
     MissingCellParameter,
+    /// Value cannot be encoded into bytes, e.g. it does not fit in its cells
+    EncodingFailed,
+    /// Device tree source does not follow the syntax
+    InvalidSyntax,
+    /// A label or path referenced by device tree source does not exist
+    UnresolvedReference,
+    /// A file named by `/include/` cannot be read
+    IncludeNotFound,
+    /// A phandle does not belong to any node of the tree
+    UnresolvedPHandle,
+    /// The blob needs a newer parser, or its version is older than its last compatible one
+    UnsupportedVersion,
+    /// A block runs past the total size, or the total size cannot even hold the header
+    BlockOutOfBounds,
+    /// Blocks or the header share bytes
+    OverlappingBlocks,
+    /// A block does not start on the alignment it needs
+    MisalignedBlock,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            ErrorKind::InvalidMagicNumber => "invalid magic number",
+            ErrorKind::NotEnoughLength => "not enough data",
+            ErrorKind::InvalidToken => "invalid token",
+            ErrorKind::ParsingFailed => "malformed data",
+            ErrorKind::MemoryAccessFailed => "memory cannot be accessed",
+            ErrorKind::MissingCellParameter => "missing cells definition",
+            ErrorKind::EncodingFailed => "value cannot be encoded",
+            ErrorKind::InvalidSyntax => "invalid syntax",
+            ErrorKind::UnresolvedReference => "unresolved reference",
+            ErrorKind::IncludeNotFound => "included file not found",
+            ErrorKind::UnresolvedPHandle => "unresolved phandle",
+            ErrorKind::UnsupportedVersion => "unsupported version",
+            ErrorKind::BlockOutOfBounds => "block out of bounds",
+            ErrorKind::OverlappingBlocks => "overlapping blocks",
+            ErrorKind::MisalignedBlock => "misaligned block",
+        };
+        f.write_str(message)
+    }
+}
+
+/// Errors for [crate::device_tree::DeviceTree] parsing
+/// Besides its [ErrorKind], it tells where it happened as far as it is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceTreeError {
+    kind: ErrorKind,
+    offset: Option<usize>,
+    #[cfg(feature = "alloc")]
+    path: Option<String>,
+    #[cfg(feature = "alloc")]
+    property: Option<String>,
+}
+
+impl DeviceTreeError {
+    /// Creates an error without any context
+    pub fn new(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            offset: None,
+            #[cfg(feature = "alloc")]
+            path: None,
+            #[cfg(feature = "alloc")]
+            property: None,
+        }
+    }
+
+    /// Get what went wrong
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Get the byte offset in the blob, or in the source with its includes pasted in
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Get the path of the node being read
+    #[cfg(feature = "alloc")]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Get the name of the property being read
+    #[cfg(feature = "alloc")]
+    pub fn property(&self) -> Option<&str> {
+        self.property.as_deref()
+    }
+
+    // the innermost context is the most precise, so none of these overwrite it
+
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.offset.get_or_insert(offset);
+        self
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn in_node(mut self, path: &str) -> Self {
+        self.path.get_or_insert_with(|| path.into());
+        self
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn in_property(mut self, name: &str) -> Self {
+        self.property.get_or_insert_with(|| name.into());
+        self
+    }
+}
+
+impl From<ErrorKind> for DeviceTreeError {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl PartialEq<ErrorKind> for DeviceTreeError {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        self.kind == *other
+    }
 }
+
+impl Display for DeviceTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {:#x}", offset)?;
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(path) = &self.path {
+            write!(f, " in {}", path)?;
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(property) = &self.property {
+            write!(f, ", property `{}`", property)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeviceTreeError {}
@@ -0,0 +1,313 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::{format, vec::Vec};
+
+use crate::device_tree::NodeRef;
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::traits::HasNamedProperty;
+
+const GPIO_CELLS: &str = "#gpio-cells";
+
+/// The flags cell of a GPIO specifier, with the bits of `dt-bindings/gpio/gpio.h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GpioFlags(u32);
+
+impl GpioFlags {
+    const ACTIVE_LOW: u32 = 1 << 0;
+    const SINGLE_ENDED: u32 = 1 << 1;
+    const OPEN_DRAIN: u32 = 1 << 2;
+    const TRANSITORY: u32 = 1 << 3;
+    const PULL_UP: u32 = 1 << 4;
+    const PULL_DOWN: u32 = 1 << 5;
+    const PULL_DISABLE: u32 = 1 << 6;
+
+    /// Wraps the raw flags cell
+    pub const fn new(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Get the raw flags cell
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// The line is asserted when low, `GPIO_ACTIVE_LOW`
+    pub fn active_low(&self) -> bool {
+        self.0 & Self::ACTIVE_LOW != 0
+    }
+
+    /// The line is only driven low, `GPIO_OPEN_DRAIN`
+    pub fn open_drain(&self) -> bool {
+        self.0 & (Self::SINGLE_ENDED | Self::OPEN_DRAIN) == Self::SINGLE_ENDED | Self::OPEN_DRAIN
+    }
+
+    /// The line is only driven high, `GPIO_OPEN_SOURCE`
+    pub fn open_source(&self) -> bool {
+        self.0 & (Self::SINGLE_ENDED | Self::OPEN_DRAIN) == Self::SINGLE_ENDED
+    }
+
+    /// The line may lose its state on suspend or reset, `GPIO_TRANSITORY`
+    pub fn transitory(&self) -> bool {
+        self.0 & Self::TRANSITORY != 0
+    }
+
+    /// The line needs its pull-up, `GPIO_PULL_UP`
+    pub fn pull_up(&self) -> bool {
+        self.0 & Self::PULL_UP != 0
+    }
+
+    /// The line needs its pull-down, `GPIO_PULL_DOWN`
+    pub fn pull_down(&self) -> bool {
+        self.0 & Self::PULL_DOWN != 0
+    }
+
+    /// The line must not be pulled, `GPIO_PULL_DISABLE`
+    pub fn pull_disable(&self) -> bool {
+        self.0 & Self::PULL_DISABLE != 0
+    }
+}
+
+/// A GPIO line of a controller with how it is wired, decoded by the controller's `#gpio-cells`
+#[derive(Clone)]
+pub struct GpioSpec<'b, 'a> {
+    controller: NodeRef<'b, 'a>,
+    cells: Vec<u32>,
+}
+
+impl<'b, 'a> GpioSpec<'b, 'a> {
+    /// Get the GPIO controller
+    pub fn controller(&self) -> &NodeRef<'b, 'a> {
+        &self.controller
+    }
+
+    /// Get the raw cells, as many as the controller's `#gpio-cells`
+    pub fn cells(&self) -> &[u32] {
+        &self.cells
+    }
+
+    /// Get the line number, the first cell
+    pub fn line(&self) -> u32 {
+        self.cells.first().copied().unwrap_or(0)
+    }
+
+    /// Get the flags, the last cell when there are two cells or more
+    pub fn flags(&self) -> GpioFlags {
+        match self.cells[..] {
+            [_, .., flags] => GpioFlags(flags),
+            _ => GpioFlags::default(),
+        }
+    }
+
+    /// Get the name of the line from the controller's `gpio-line-names`
+    pub fn line_name(&self) -> Option<&'b str> {
+        self.controller
+            .gpio_line_names()
+            .get(self.line() as usize)
+            .copied()
+            .filter(|name| !name.is_empty())
+    }
+}
+
+/// The state a hog puts its lines in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HogState {
+    /// `input`
+    Input,
+    /// `output-low`
+    OutputLow,
+    /// `output-high`
+    OutputHigh,
+}
+
+/// A `gpio-hog` child of a controller, which sets up lines when the controller is probed
+#[derive(Clone)]
+pub struct GpioHog<'b, 'a> {
+    node: NodeRef<'b, 'a>,
+    lines: Vec<GpioSpec<'b, 'a>>,
+    state: HogState,
+}
+
+impl<'b, 'a> GpioHog<'b, 'a> {
+    /// Get the hog node
+    pub fn node(&self) -> &NodeRef<'b, 'a> {
+        &self.node
+    }
+
+    /// Get the lines of its `gpios`, of the controller it is a child of
+    pub fn lines(&self) -> &[GpioSpec<'b, 'a>] {
+        &self.lines
+    }
+
+    /// Get the state to put the lines in
+    pub fn state(&self) -> HogState {
+        self.state
+    }
+
+    /// Get its `line-name`
+    pub fn line_name(&self) -> Option<&'b str> {
+        self.node.node().find_prop("line-name")?.as_str()
+    }
+}
+
+/// An entry of `gpio-ranges`: lines of a GPIO controller routed to pins of a pin controller
+#[derive(Clone)]
+pub struct GpioRange<'b, 'a> {
+    pinctrl: NodeRef<'b, 'a>,
+    gpio_offset: u32,
+    pin_offset: u32,
+    count: u32,
+    group: Option<&'b str>,
+}
+
+impl<'b, 'a> GpioRange<'b, 'a> {
+    /// Get the pin controller
+    pub fn pinctrl(&self) -> &NodeRef<'b, 'a> {
+        &self.pinctrl
+    }
+
+    /// Get the first GPIO line of the range
+    pub fn gpio_offset(&self) -> u32 {
+        self.gpio_offset
+    }
+
+    /// Get the first pin of the range
+    pub fn pin_offset(&self) -> u32 {
+        self.pin_offset
+    }
+
+    /// Get the number of lines, 0 when they are given by a pin group instead
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Get the pin group of a range without count, from `gpio-ranges-group-names`
+    pub fn group(&self) -> Option<&'b str> {
+        self.group
+    }
+
+    /// Get the pin of a GPIO line, `None` if the range does not cover it or its pin overflows
+    pub fn pin(&self, line: u32) -> Option<u32> {
+        let offset = line.checked_sub(self.gpio_offset)?;
+        (offset < self.count).then_some(())?;
+        self.pin_offset.checked_add(offset)
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Decode its `<name>-gpios`, or the legacy `<name>-gpio`, or its `gpios` if `name` is empty
+    /// An empty entry, a zero phandle, is `None` and keeps its place
+    pub fn gpios(&self, name: &str) -> Result<Vec<Option<GpioSpec<'b, 'a>>>> {
+        let list = match name {
+            "" => "gpios".into(),
+            name if self.find_prop(&format!("{}-gpios", name)).is_some() => {
+                format!("{}-gpios", name)
+            }
+            name => format!("{}-gpio", name),
+        };
+        self.phandle_args(&list, GPIO_CELLS)
+            .map(|entry| {
+                Ok(entry?.map(|entry| GpioSpec {
+                    controller: entry.provider().clone(),
+                    cells: entry.args().to_vec(),
+                }))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    /// Get the names of its lines from `gpio-line-names`, an empty one for unnamed lines
+    pub fn gpio_line_names(&self) -> Vec<&'b str> {
+        self.node()
+            .find_prop("gpio-line-names")
+            .map(|names| names.as_str_list().collect())
+            .unwrap_or_default()
+    }
+
+    /// Decode its `gpio-hog` children
+    pub fn gpio_hogs(&self) -> Result<Vec<GpioHog<'b, 'a>>> {
+        self.read_gpio_hogs()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    fn read_gpio_hogs(&self) -> Result<Vec<GpioHog<'b, 'a>>> {
+        let cells = self
+            .prop_u32(GPIO_CELLS)
+            .ok_or(ErrorKind::MissingCellParameter)? as usize;
+        if cells == 0 {
+            return Err(ErrorKind::ParsingFailed.into());
+        }
+        self.children()
+            .filter(|child| child.find_prop("gpio-hog").is_some())
+            .map(|child| {
+                self.read_gpio_hog(&child, cells)
+                    .map_err(|err| err.in_node(&child.path()))
+            })
+            .collect()
+    }
+
+    // a hog child of this controller, whose specifiers take `cells` cells
+    fn read_gpio_hog(&self, hog: &NodeRef<'b, 'a>, cells: usize) -> Result<GpioHog<'b, 'a>> {
+        let state = if hog.find_prop("output-high").is_some() {
+            HogState::OutputHigh
+        } else if hog.find_prop("output-low").is_some() {
+            HogState::OutputLow
+        } else if hog.find_prop("input").is_some() {
+            HogState::Input
+        } else {
+            return Err(ErrorKind::MissingCellParameter.into());
+        };
+        let gpios = hog
+            .find_prop("gpios")
+            .ok_or(ErrorKind::MissingCellParameter)?;
+        let specifiers = gpios.cells().map_err(|err| err.in_property(gpios.name()))?;
+        if specifiers.is_empty() || specifiers.len() % cells != 0 {
+            return Err(DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(gpios.name()));
+        }
+        let lines = specifiers
+            .chunks_exact(cells)
+            .map(|cells| GpioSpec {
+                controller: self.clone(),
+                cells: cells.to_vec(),
+            })
+            .collect();
+        Ok(GpioHog {
+            node: hog.clone(),
+            lines,
+            state,
+        })
+    }
+
+    /// Decode its `gpio-ranges`, `<&pinctrl gpio-offset pin-offset count>` entries
+    pub fn gpio_ranges(&self) -> Result<Vec<GpioRange<'b, 'a>>> {
+        self.read_gpio_ranges()
+            .map_err(|err| err.in_node(&self.path()).in_property("gpio-ranges"))
+    }
+
+    fn read_gpio_ranges(&self) -> Result<Vec<GpioRange<'b, 'a>>> {
+        let ranges = match self.find_prop("gpio-ranges") {
+            Some(ranges) => ranges.cells()?,
+            None => return Ok(Vec::new()),
+        };
+        if ranges.len() % 4 != 0 {
+            return Err(ErrorKind::ParsingFailed.into());
+        }
+        let mut groups = self
+            .node()
+            .find_prop("gpio-ranges-group-names")
+            .map(|names| names.as_str_list());
+        ranges
+            .chunks_exact(4)
+            .map(|range| {
+                let group = groups.as_mut().and_then(Iterator::next);
+                Ok(GpioRange {
+                    pinctrl: self.resolve(range[0])?,
+                    gpio_offset: range[1],
+                    pin_offset: range[2],
+                    count: range[3],
+                    group: group.filter(|name| range[3] == 0 && !name.is_empty()),
+                })
+            })
+            .collect()
+    }
+}
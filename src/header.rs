@@ -1,7 +1,23 @@
-use crate::byte_utils::read_aligned_be_u32;
-use crate::error::{DeviceTreeError, Result};
+use crate::byte_utils::{read_aligned_be_u32, BLOCK_SIZE};
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::walker::Validation;
 
-#[derive(Debug)]
+pub(crate) const HEADER_SIZE: usize = 40;
+pub(crate) const FDT_MAGIC: u32 = 0xd00dfeed;
+// the newest layout understood, blobs only readable by newer parsers are rejected
+pub(crate) const LAST_SUPPORTED_VERSION: u32 = 17;
+
+// byte offsets of the header fields that errors point at
+const TOTAL_SIZE: usize = 4;
+const OFF_DT_STRUCT: usize = 8;
+const OFF_DT_STRINGS: usize = 12;
+const OFF_MEM_RESERVED: usize = 16;
+const VERSION: usize = 20;
+const LAST_COMP_VERSION: usize = 24;
+
+// without `alloc` only the fields needed for walking are read
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct DeviceTreeHeader {
     pub magic: u32,
     pub total_size: u32,
@@ -16,22 +32,149 @@ pub(crate) struct DeviceTreeHeader {
 }
 
 impl DeviceTreeHeader {
+    /// Fields introduced after the version of the blob are left zero
     pub(crate) fn from_bytes(data: &[u8]) -> Result<DeviceTreeHeader> {
-        if data.len() < 10 {
-            Err(DeviceTreeError::NotEnoughLength)
-        } else {
-            Ok(Self {
-                magic: read_aligned_be_u32(data, 0).unwrap(),
-                total_size: read_aligned_be_u32(data, 1).unwrap(),
-                off_dt_struct: read_aligned_be_u32(data, 2).unwrap(),
-                off_dt_strings: read_aligned_be_u32(data, 3).unwrap(),
-                off_mem_reserved: read_aligned_be_u32(data, 4).unwrap(),
-                version: read_aligned_be_u32(data, 5).unwrap(),
-                last_comp_version: read_aligned_be_u32(data, 6).unwrap(),
-                boot_cpu_id: read_aligned_be_u32(data, 7).unwrap(),
-                size_dt_strings: read_aligned_be_u32(data, 8).unwrap(),
-                size_dt_struct: read_aligned_be_u32(data, 9).unwrap(),
-            })
+        let read = |index| read_aligned_be_u32(data, index).ok_or(ErrorKind::NotEnoughLength);
+        let version = read(5)?;
+        if data.len() < header_size(version) {
+            return Err(ErrorKind::NotEnoughLength.into());
+        }
+        let since = |first, index| if version >= first { read(index) } else { Ok(0) };
+        Ok(Self {
+            magic: read(0)?,
+            total_size: read(1)?,
+            off_dt_struct: read(2)?,
+            off_dt_strings: read(3)?,
+            off_mem_reserved: read(4)?,
+            version,
+            last_comp_version: read(6)?,
+            boot_cpu_id: since(2, 7)?,
+            size_dt_strings: since(3, 8)?,
+            size_dt_struct: since(17, 9)?,
+        })
+    }
+
+    /// Offset and size of the structure block
+    /// Before version 17 its size is not recorded, it runs up to the next block
+    pub(crate) fn struct_block(&self) -> (u32, u32) {
+        let size = match self.version {
+            17.. => self.size_dt_struct,
+            _ => self.end_of(self.off_dt_struct) - self.off_dt_struct,
+        };
+        (self.off_dt_struct, size)
+    }
+
+    /// Offset and size of the strings block
+    /// Before version 3 its size is not recorded, it runs up to the next block
+    pub(crate) fn strings_block(&self) -> (u32, u32) {
+        let size = match self.version {
+            3.. => self.size_dt_strings,
+            _ => self.end_of(self.off_dt_strings) - self.off_dt_strings,
+        };
+        (self.off_dt_strings, size)
+    }
+
+    // the start of the block following `offset`, or the end of the blob
+    fn end_of(&self, offset: u32) -> u32 {
+        [
+            self.off_mem_reserved,
+            self.off_dt_struct,
+            self.off_dt_strings,
+        ]
+        .into_iter()
+        .filter(|start| *start > offset)
+        .fold(self.total_size, u32::min)
+        .max(offset)
+    }
+
+    /// Check the invariants of the header, the lenient ones are what walking the blob relies on
+    pub(crate) fn validate(&self, validation: Validation) -> Result<()> {
+        let fail = |kind: ErrorKind, offset| Err(DeviceTreeError::from(kind).at(offset));
+        if self.last_comp_version > LAST_SUPPORTED_VERSION {
+            return fail(ErrorKind::UnsupportedVersion, LAST_COMP_VERSION);
+        }
+        if (self.total_size as usize) < header_size(self.version) {
+            return fail(ErrorKind::BlockOutOfBounds, TOTAL_SIZE);
+        }
+        if self.off_dt_struct as usize % BLOCK_SIZE != 0 {
+            return fail(ErrorKind::MisalignedBlock, OFF_DT_STRUCT);
+        }
+        let (struct_start, struct_size) = self.struct_block();
+        let (strings_start, strings_size) = self.strings_block();
+        let in_bounds = |start: u32, size: u32| {
+            start
+                .checked_add(size)
+                .is_some_and(|end| end <= self.total_size)
+        };
+        if !in_bounds(struct_start, struct_size) {
+            return fail(ErrorKind::BlockOutOfBounds, OFF_DT_STRUCT);
+        }
+        if !in_bounds(strings_start, strings_size) {
+            return fail(ErrorKind::BlockOutOfBounds, OFF_DT_STRINGS);
         }
+        if validation == Validation::Lenient {
+            return Ok(());
+        }
+
+        if self.version < self.last_comp_version {
+            return fail(ErrorKind::UnsupportedVersion, VERSION);
+        }
+        if !in_bounds(self.off_mem_reserved, 16) {
+            return fail(ErrorKind::BlockOutOfBounds, OFF_MEM_RESERVED);
+        }
+        if self.off_mem_reserved % 8 != 0 {
+            return fail(ErrorKind::MisalignedBlock, OFF_MEM_RESERVED);
+        }
+        let header = 0..header_size(self.version) as u32;
+        let structs = struct_start..struct_start + struct_size;
+        let strings = strings_start..strings_start + strings_size;
+        let overlap = |a: &core::ops::Range<u32>, b: &core::ops::Range<u32>| {
+            a.start < b.end && b.start < a.end
+        };
+        // the size of the reservation block is only known by walking it, only its start is checked
+        if [&header, &structs, &strings]
+            .iter()
+            .any(|block| block.contains(&self.off_mem_reserved))
+        {
+            return fail(ErrorKind::OverlappingBlocks, OFF_MEM_RESERVED);
+        }
+        if overlap(&header, &structs) || overlap(&strings, &structs) {
+            return fail(ErrorKind::OverlappingBlocks, OFF_DT_STRUCT);
+        }
+        if overlap(&header, &strings) {
+            return fail(ErrorKind::OverlappingBlocks, OFF_DT_STRINGS);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn to_bytes(self) -> [u8; HEADER_SIZE] {
+        let fields = [
+            self.magic,
+            self.total_size,
+            self.off_dt_struct,
+            self.off_dt_strings,
+            self.off_mem_reserved,
+            self.version,
+            self.last_comp_version,
+            self.boot_cpu_id,
+            self.size_dt_strings,
+            self.size_dt_struct,
+        ];
+        let mut bytes = [0u8; HEADER_SIZE];
+        for (i, field) in fields.iter().enumerate() {
+            bytes[i * 4..(i + 1) * 4].copy_from_slice(&field.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+// the header grew a field in versions 2, 3 and 17
+fn header_size(version: u32) -> usize {
+    match version {
+        0..=1 => 28,
+        2 => 32,
+        3..=16 => 36,
+        _ => HEADER_SIZE,
     }
 }
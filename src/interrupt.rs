@@ -0,0 +1,347 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::error::{ErrorKind, Result};
+use crate::node::DeviceTreeNode;
+use crate::traits::HasNamedProperty;
+
+// interrupt parents followed before giving up on a loop of `interrupt-parent`s
+const MAX_PARENT_HOPS: usize = 64;
+
+/// Trigger type of an interrupt, as encoded by the `IRQ_TYPE_*` flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// Low to high edge triggered
+    EdgeRising,
+    /// High to low edge triggered
+    EdgeFalling,
+    /// Triggered on both edges
+    EdgeBoth,
+    /// Active high level-sensitive
+    LevelHigh,
+    /// Active low level-sensitive
+    LevelLow,
+}
+
+impl Trigger {
+    /// Decode the low 4 bits of a flags cell, `None` if no trigger type is set
+    pub fn from_flags(flags: u32) -> Option<Self> {
+        match flags & 0xf {
+            1 => Some(Trigger::EdgeRising),
+            2 => Some(Trigger::EdgeFalling),
+            3 => Some(Trigger::EdgeBoth),
+            4 => Some(Trigger::LevelHigh),
+            8 => Some(Trigger::LevelLow),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of an interrupt routed by an ARM GIC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GicKind {
+    /// Shared peripheral interrupt
+    Spi,
+    /// Private peripheral interrupt
+    Ppi,
+}
+
+/// A three cells GIC specifier: kind, number and flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GicInterrupt {
+    /// Whether the interrupt is shared or private to a cpu
+    pub kind: GicKind,
+    /// The number relative to the first interrupt of its kind
+    pub number: u32,
+    /// The trigger type from the flags cell
+    pub trigger: Option<Trigger>,
+}
+
+impl GicInterrupt {
+    /// Get the hardware interrupt id, SPIs start at 32 and PPIs at 16
+    pub fn irq(&self) -> u32 {
+        match self.kind {
+            GicKind::Spi => self.number + 32,
+            GicKind::Ppi => self.number + 16,
+        }
+    }
+}
+
+/// An interrupt of a node, its cells are interpreted by the controller it is routed to
+#[derive(Clone)]
+pub struct InterruptSpecifier<'b, 'a> {
+    controller: NodeRef<'b, 'a>,
+    cells: Vec<u32>,
+}
+
+impl<'b, 'a> InterruptSpecifier<'b, 'a> {
+    /// Get the interrupt controller
+    pub fn controller(&self) -> &NodeRef<'b, 'a> {
+        &self.controller
+    }
+
+    /// Get the raw cells, as many as the controller's `#interrupt-cells`
+    pub fn cells(&self) -> &[u32] {
+        &self.cells
+    }
+
+    /// Get the interrupt number of the one or two cells encodings
+    pub fn number(&self) -> Option<u32> {
+        match self.cells[..] {
+            [number] | [number, _] => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Get the trigger type of the two cells encoding `<number flags>`
+    pub fn trigger(&self) -> Option<Trigger> {
+        match self.cells[..] {
+            [_, flags] => Trigger::from_flags(flags),
+            _ => None,
+        }
+    }
+
+    /// Decode the three cells encoding of the ARM GIC
+    pub fn gic(&self) -> Option<GicInterrupt> {
+        let kind = match self.cells.first()? {
+            0 => GicKind::Spi,
+            1 => GicKind::Ppi,
+            _ => return None,
+        };
+        match self.cells[..] {
+            [_, number, flags, ..] => Some(GicInterrupt {
+                kind,
+                number,
+                trigger: Trigger::from_flags(flags),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Get the source id of the one cell encoding of the RISC-V PLIC, where 0 means no interrupt
+    pub fn plic(&self) -> Option<u32> {
+        match self.cells[..] {
+            [source] if source != 0 => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Find the controller its interrupts are routed to
+    /// Follows `interrupt-parent`, or the tree parent without one, up to a node with `#interrupt-cells`
+    pub fn interrupt_parent(&self) -> Result<Option<NodeRef<'b, 'a>>> {
+        let mut current = self.clone();
+        for _ in 0..MAX_PARENT_HOPS {
+            let next = match current.prop_u32("interrupt-parent") {
+                Some(phandle) => current.resolve(phandle)?,
+                None => match current.parent() {
+                    Some(parent) => parent,
+                    None => return Ok(None),
+                },
+            };
+            if next.find_prop("#interrupt-cells").is_some() {
+                return Ok(Some(next));
+            }
+            current = next;
+        }
+        Err(ErrorKind::ParsingFailed.into())
+    }
+
+    /// Decode its `interrupts-extended`, or its `interrupts` against the interrupt parent
+    /// An interrupt for a nexus is routed through its `interrupt-map` down to a controller,
+    /// keyed by the first cells of its `reg` like `of_irq_parse_one` does
+    pub fn interrupts(&self) -> Result<Vec<InterruptSpecifier<'b, 'a>>> {
+        self.read_interrupts()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    fn read_interrupts(&self) -> Result<Vec<InterruptSpecifier<'b, 'a>>> {
+        let mut interrupts = Vec::new();
+        if let Some(prop) = self.find_prop("interrupts-extended") {
+            let cells = prop.cells().map_err(|err| err.in_property(prop.name()))?;
+            let mut rest = &cells[..];
+            while let [phandle, specifier @ ..] = rest {
+                let (interrupt, next) = self
+                    .resolve(*phandle)
+                    .and_then(|parent| {
+                        let (cells, next) = split_specifier(&parent, specifier)?;
+                        Ok((self.route_interrupt(parent, cells)?, next))
+                    })
+                    .map_err(|err| err.in_property(prop.name()))?;
+                interrupts.push(interrupt);
+                rest = next;
+            }
+        } else if let Some(prop) = self.find_prop("interrupts") {
+            let parent = self
+                .interrupt_parent()?
+                .ok_or(ErrorKind::MissingCellParameter)?;
+            let cells = prop.cells().map_err(|err| err.in_property(prop.name()))?;
+            let mut rest = &cells[..];
+            while !rest.is_empty() {
+                let (interrupt, next) = split_specifier(&parent, rest)
+                    .and_then(|(cells, next)| {
+                        Ok((self.route_interrupt(parent.clone(), cells)?, next))
+                    })
+                    .map_err(|err| err.in_property(prop.name()))?;
+                interrupts.push(interrupt);
+                rest = next;
+            }
+        }
+        Ok(interrupts)
+    }
+
+    // carries a specifier of its interrupt parent `parent` through the maps of the nexus on the way
+    fn route_interrupt(
+        &self,
+        parent: NodeRef<'b, 'a>,
+        cells: Vec<u32>,
+    ) -> Result<InterruptSpecifier<'b, 'a>> {
+        if parent.find_prop("interrupt-map").is_none() {
+            return Ok(InterruptSpecifier {
+                controller: parent,
+                cells,
+            });
+        }
+        // missing address cells are zeros
+        let address_cells = parent.prop_u32("#address-cells").unwrap_or(0) as usize;
+        let reg = match self.find_prop("reg") {
+            Some(reg) => reg.cells()?,
+            None => Vec::new(),
+        };
+        let unit_address = (0..address_cells)
+            .map(|i| reg.get(i).copied().unwrap_or(0))
+            .collect::<Vec<_>>();
+        parent
+            .map_interrupt(&unit_address, &cells)?
+            .ok_or(ErrorKind::ParsingFailed.into())
+    }
+
+    // see `DeviceTree::map_interrupt`
+    fn map_interrupt(
+        &self,
+        unit_address: &[u32],
+        specifier: &[u32],
+    ) -> Result<Option<InterruptSpecifier<'b, 'a>>> {
+        let mut node = self.clone();
+        let mut unit_address = unit_address.to_vec();
+        let mut specifier = specifier.to_vec();
+        for _ in 0..MAX_PARENT_HOPS {
+            let is_controller = node.find_prop("interrupt-controller").is_some();
+            let map = match node.find_prop("interrupt-map") {
+                Some(map) => map.cells()?,
+                None if is_controller => {
+                    return Ok(Some(InterruptSpecifier {
+                        controller: node,
+                        cells: specifier,
+                    }));
+                }
+                None => return Err(ErrorKind::MissingCellParameter.into()),
+            };
+            let address_cells = node.prop_u32("#address-cells").unwrap_or(0) as usize;
+            let interrupt_cells = node
+                .prop_u32("#interrupt-cells")
+                .ok_or(ErrorKind::MissingCellParameter)? as usize;
+            if unit_address.len() != address_cells || specifier.len() != interrupt_cells {
+                return Err(ErrorKind::ParsingFailed.into());
+            }
+            let key = [&unit_address[..], &specifier[..]].concat();
+            let mask = match node.find_prop("interrupt-map-mask") {
+                Some(mask) => mask.cells()?,
+                None => [u32::MAX].repeat(key.len()),
+            };
+            if mask.len() != key.len() {
+                return Err(ErrorKind::ParsingFailed.into());
+            }
+
+            let mut rest = &map[..];
+            let mut found = None;
+            while !rest.is_empty() {
+                if rest.len() <= key.len() {
+                    return Err(ErrorKind::ParsingFailed.into());
+                }
+                let (child, row) = rest.split_at(key.len());
+                let parent = node.resolve(row[0])?;
+                let parent_address_cells = parent.prop_u32("#address-cells").unwrap_or(0) as usize;
+                let parent_interrupt_cells = parent
+                    .prop_u32("#interrupt-cells")
+                    .ok_or(ErrorKind::MissingCellParameter)?
+                    as usize;
+                let size = 1 + parent_address_cells + parent_interrupt_cells;
+                if row.len() < size {
+                    return Err(ErrorKind::ParsingFailed.into());
+                }
+                let matched = child
+                    .iter()
+                    .zip(&key)
+                    .zip(&mask)
+                    .all(|((child, key), mask)| (child ^ key) & mask == 0);
+                if matched {
+                    let (address, specifier) = row[1..size].split_at(parent_address_cells);
+                    found = Some((parent, address.to_vec(), specifier.to_vec()));
+                    break;
+                }
+                rest = &row[size..];
+            }
+            match found {
+                Some((parent, address, cells)) => {
+                    node = parent;
+                    unit_address = address;
+                    specifier = cells;
+                }
+                // like `of_irq_parse_raw`, a controller takes the interrupts its map has no row for
+                None if is_controller => {
+                    return Ok(Some(InterruptSpecifier {
+                        controller: node,
+                        cells: specifier,
+                    }));
+                }
+                None => return Ok(None),
+            }
+        }
+        Err(ErrorKind::ParsingFailed.into())
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Decode the interrupts of `node`, see [NodeRef::interrupts]
+    pub fn interrupts<'b>(
+        &'b self,
+        node: &'b DeviceTreeNode<'a>,
+    ) -> Result<Vec<InterruptSpecifier<'b, 'a>>> {
+        self.node_ref(node)
+            .ok_or(ErrorKind::ParsingFailed)?
+            .interrupts()
+    }
+
+    /// Route an interrupt of a child of the nexus `node` through `interrupt-map`s
+    /// The unit address and specifier are masked by `interrupt-map-mask` and matched against
+    /// the map, which is followed until a node with `interrupt-controller` is reached
+    /// A controller which also has a map takes the interrupts its map has no row for
+    /// Returns `None` if a map of a node which is not a controller has no row for the interrupt
+    pub fn map_interrupt<'b>(
+        &'b self,
+        node: &'b DeviceTreeNode<'a>,
+        unit_address: &[u32],
+        specifier: &[u32],
+    ) -> Result<Option<InterruptSpecifier<'b, 'a>>> {
+        self.node_ref(node)
+            .ok_or(ErrorKind::ParsingFailed)?
+            .map_interrupt(unit_address, specifier)
+    }
+}
+
+// takes one specifier sized by the controller's `#interrupt-cells` off the front of `cells`
+fn split_specifier<'c>(controller: &NodeRef, cells: &'c [u32]) -> Result<(Vec<u32>, &'c [u32])> {
+    let size = controller
+        .prop_u32("#interrupt-cells")
+        .ok_or(ErrorKind::MissingCellParameter)? as usize;
+    if size == 0 || size > cells.len() {
+        return Err(ErrorKind::ParsingFailed.into());
+    }
+    let (specifier, rest) = cells.split_at(size);
+    Ok((specifier.to_vec(), rest))
+}
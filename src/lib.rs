@@ -5,23 +5,72 @@
 //!
 //! Parses a device tree blob to a human-friendly data structure.
 //!
-//! The no [std] but [alloc] library is required.
+//! The no [std] but [alloc] library is required for the tree, the [walker::Fdt] reads a blob
+//! in place and is available even without the `alloc` feature.
 
-#[cfg(not(feature = "std"))]
+#[cfg(all(feature = "alloc", not(feature = "std")))]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
 pub use device_tree::DeviceTree;
+pub use walker::Fdt;
 
 mod byte_utils;
 mod header;
 
+/// `ClockProvider` and `Clock`, the clock bindings
+#[cfg(feature = "alloc")]
+pub mod clock;
 /// `DeviceTree`
+#[cfg(feature = "alloc")]
 pub mod device_tree;
+/// `DmaWindow`
+#[cfg(feature = "alloc")]
+pub mod dma;
+#[cfg(feature = "alloc")]
+mod dts;
+#[cfg(feature = "alloc")]
+mod dts_writer;
 /// `DeviceTreeError`
 pub mod error;
+/// `GpioSpec`, the GPIO bindings
+#[cfg(feature = "alloc")]
+pub mod gpio;
+/// `InterruptSpecifier`
+#[cfg(feature = "alloc")]
+pub mod interrupt;
+/// `MemoryRange`, the usable RAM of a tree
+#[cfg(feature = "alloc")]
+pub mod memory_map;
 /// `DeviceTreeNode`
+#[cfg(feature = "alloc")]
 pub mod node;
+/// `PhandleArgs`, entries of phandle lists such as `clocks`
+#[cfg(feature = "alloc")]
+pub mod phandle_args;
+/// `PinctrlState` and `PinConfig`, the pin control bindings
+#[cfg(feature = "alloc")]
+pub mod pinctrl;
 /// `NodeProperty`
+#[cfg(feature = "alloc")]
 pub mod prop;
+/// `RegEntry`
+#[cfg(feature = "alloc")]
+pub mod reg;
+/// `MemoryReservation`
+pub mod reservation;
+/// `ReservedMemory`, the regions of `/reserved-memory`
+#[cfg(feature = "alloc")]
+pub mod reserved_memory;
+/// `PropertyRegistry`, how property values are decoded
+#[cfg(feature = "alloc")]
+pub mod schema;
 /// Traits for the crate
+#[cfg(feature = "alloc")]
 pub mod traits;
+#[cfg(feature = "alloc")]
+mod translate;
+/// `Fdt`, the allocation-free walker
+pub mod walker;
+#[cfg(feature = "alloc")]
+mod writer;
@@ -0,0 +1,108 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::DeviceTree;
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::reserved_memory::Placement;
+use crate::traits::HasNamedProperty;
+
+/// A range of RAM the operating system may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRange {
+    /// Its first CPU physical address
+    pub start: u64,
+    /// Its length in bytes
+    pub size: u64,
+    /// The `numa-node-id` of the memory node it belongs to
+    pub numa_node: Option<u32>,
+}
+
+impl MemoryRange {
+    /// Get the address right after it
+    pub fn end(&self) -> u64 {
+        self.start.saturating_add(self.size)
+    }
+
+    // what is left of it out of the hole `start..end`, before and after it
+    fn punch(self, start: u64, end: u64) -> [Option<MemoryRange>; 2] {
+        if end <= self.start || start >= self.end() {
+            return [Some(self), None];
+        }
+        let before = (start > self.start).then_some(MemoryRange {
+            size: start - self.start,
+            ..self
+        });
+        let after = (end < self.end()).then(|| MemoryRange {
+            start: end,
+            size: self.end() - end,
+            ..self
+        });
+        [before, after]
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Gather the RAM of the available `device_type = "memory"` nodes, whatever their names,
+    /// less the memory reservation block and the `no-map` regions of `/reserved-memory`
+    /// The ranges are sorted, those adjacent within a NUMA node are merged
+    /// Fails if a region of `/reserved-memory` cannot be read, it may have to be cut out
+    pub fn memory_map(&self) -> Result<Vec<MemoryRange>> {
+        let mut ranges = Vec::new();
+        for node in self {
+            let is_memory = node
+                .find_prop("device_type")
+                .is_some_and(|it| it.as_str() == Some("memory"));
+            if !is_memory || !node.is_available() {
+                continue;
+            }
+            let regs = node.cpu_regs().ok_or_else(|| {
+                DeviceTreeError::from(ErrorKind::ParsingFailed)
+                    .in_node(&node.path())
+                    .in_property("reg")
+            })?;
+            let numa_node = node.prop_u32("numa-node-id");
+            ranges.extend(regs.into_iter().map(|(start, size)| MemoryRange {
+                start,
+                size,
+                numa_node,
+            }));
+        }
+
+        let mut holes = self
+            .memory_reservations()
+            .map(|reservation| (reservation.address(), reservation.size()))
+            .collect::<Vec<_>>();
+        let reserved = self.reserved_memory()?;
+        if let Some(err) = reserved.errors().first() {
+            return Err(err.clone());
+        }
+        for region in reserved.regions() {
+            if let (true, Placement::Static(reg)) = (region.no_map(), region.placement()) {
+                holes.extend_from_slice(reg);
+            }
+        }
+        for (start, size) in holes {
+            let end = start.saturating_add(size);
+            ranges = ranges
+                .into_iter()
+                .flat_map(|range| range.punch(start, end))
+                .flatten()
+                .collect();
+        }
+
+        ranges.retain(|range| range.size != 0);
+        ranges.sort_unstable_by_key(|range| (range.start, range.size));
+        let mut merged: Vec<MemoryRange> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if last.numa_node == range.numa_node && range.start <= last.end() => {
+                    last.size = last.end().max(range.end()) - last.start;
+                }
+                _ => merged.push(range),
+            }
+        }
+        Ok(merged)
+    }
+}
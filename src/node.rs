@@ -1,145 +1,125 @@
 #[cfg(not(feature = "std"))]
-use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 #[cfg(not(feature = "std"))]
 use core::fmt::{Display, Formatter, Write};
 #[cfg(feature = "std")]
 use std::fmt::{Display, Formatter, Write};
 #[cfg(feature = "std")]
-use std::{borrow::ToOwned, string::String, vec::Vec};
+use std::{borrow::Cow, string::String, vec::Vec};
 
-use crate::byte_utils::{align_size, locate_block, read_aligned_be_u32, read_aligned_name};
-use crate::device_tree::InheritedValues;
-use crate::error::DeviceTreeError;
 use crate::error::Result;
-use crate::header::DeviceTreeHeader;
+use crate::error::{DeviceTreeError, ErrorKind};
 use crate::prop::{NodeProperty, PropertyValue};
+use crate::schema::PropertyRegistry;
 use crate::traits::{HasNamedChildNode, HasNamedProperty};
+use crate::walker::{FdtNode, InheritedValues};
+
+// nesting allowed in a blob, the same limit as Linux has
+pub(crate) const MAX_DEPTH: usize = 64;
 
 /// Node of [crate::device_tree::DeviceTree]
-/// Contains owned children and properties
-pub struct DeviceTreeNode {
-    pub(crate) block_count: usize,
-    name: String,
-    props: Vec<NodeProperty>,
-    nodes: Vec<DeviceTreeNode>,
+/// Contains owned children and properties, the name is borrowed from the blob unless renamed
+pub struct DeviceTreeNode<'a> {
+    name: Cow<'a, str>,
+    inherited: InheritedValues,
+    props: Vec<NodeProperty<'a>>,
+    nodes: Vec<DeviceTreeNode<'a>>,
 }
 
-impl DeviceTreeNode {
-    pub(crate) fn from_bytes(
-        data: &[u8],
-        header: &DeviceTreeHeader,
-        start: usize,
-        inherited: InheritedValues,
+impl<'a> DeviceTreeNode<'a> {
+    /// Creates an empty node which can be attached by [DeviceTreeNode::add_child]
+    /// A name holding `\0` or `/` makes [crate::device_tree::DeviceTree::to_bytes] fail
+    pub fn new(name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            name: name.into(),
+            inherited: InheritedValues::default(),
+            props: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn from_fdt(node: FdtNode<'a>, registry: &dyn PropertyRegistry) -> Result<Self> {
+        Self::from_fdt_at(node, registry, &mut String::new(), 0)
+    }
+
+    // `path` is the one of the parent, errors are given the path of the node they happen in
+    fn from_fdt_at(
+        node: FdtNode<'a>,
+        registry: &dyn PropertyRegistry,
+        path: &mut String,
+        depth: usize,
     ) -> Result<Self> {
-        let block_start = align_size(start);
-        if let Some(begin_node) = read_aligned_be_u32(data, block_start) {
-            if begin_node == 0x1 {
-                if let Some(name) = read_aligned_name(data, block_start + 1) {
-                    let mut props = Vec::<NodeProperty>::new();
-                    let mut nodes = Vec::<DeviceTreeNode>::new();
-                    let mut owned = InheritedValues::new();
-                    let name_blocks = if align_size(name.len() + 1) == 0 {
-                        // including zero-length name which occupied one block
-                        1
-                    } else {
-                        // it only contains asciis so does equal to byte length
-                        align_size(name.len() + 1)
-                    };
-
-                    let mut current_block = block_start + name_blocks + 1;
-
-                    // prepare owned
-
-                    while let Some(token) = read_aligned_be_u32(data, current_block) {
-                        match token {
-                            0x3 => {
-                                if let Some((prop_name, size, count)) =
-                                    NodeProperty::read_meta(data, header, current_block)
-                                {
-                                    if prop_name.starts_with('#') {
-                                        if let Ok(prop) = NodeProperty::from_meta(
-                                            data,
-                                            (prop_name.clone(), size, count),
-                                            current_block,
-                                            &inherited,
-                                            &owned,
-                                        ) {
-                                            if let PropertyValue::Integer(v) = prop.value() {
-                                                current_block += prop.block_count;
-                                                owned.insert(prop_name, *v);
-                                            } else {
-                                                return Err(DeviceTreeError::ParsingFailed);
-                                            }
-                                        } else {
-                                            return Err(DeviceTreeError::ParsingFailed);
-                                        }
-                                    } else {
-                                        current_block += count;
-                                        continue;
-                                    }
-                                } else {
-                                    return Err(DeviceTreeError::ParsingFailed);
-                                }
-                            }
-                            _ => break,
-                        };
-                    }
-
-                    current_block = block_start + name_blocks + 1;
-
-                    // parse the remaining props and nodes
-
-                    while let Some(token) = read_aligned_be_u32(data, current_block) {
-                        match token {
-                            0x3 => {
-                                if let Ok(prop) = NodeProperty::from_bytes(
-                                    data,
-                                    header,
-                                    locate_block(current_block),
-                                    &inherited,
-                                    &owned,
-                                ) {
-                                    current_block += prop.block_count;
-                                    props.push(prop);
-                                } else {
-                                    return Err(DeviceTreeError::ParsingFailed);
-                                }
-                            }
-                            0x1 => {
-                                if let Ok(node) = DeviceTreeNode::from_bytes(
-                                    data,
-                                    header,
-                                    locate_block(current_block),
-                                    owned.clone(),
-                                ) {
-                                    current_block += node.block_count;
-                                    nodes.push(node);
-                                } else {
-                                    return Err(DeviceTreeError::ParsingFailed);
-                                }
-                            }
-                            0x2 | 0x9 => {
-                                current_block += 1;
-                                break;
-                            }
-                            _ => current_block += 1,
-                        };
-                    }
-                    Ok(Self {
-                        block_count: current_block - block_start,
-                        name: name.to_owned(),
-                        props,
-                        nodes,
-                    })
-                } else {
-                    Err(DeviceTreeError::ParsingFailed)
-                }
-            } else {
-                Err(DeviceTreeError::InvalidToken)
+        let parent_len = path.len();
+        if depth > 0 {
+            path.push('/');
+            path.push_str(node.name());
+        }
+        let res = Self::read_fdt(node, registry, path, depth)
+            .map_err(|err| err.in_node(if path.is_empty() { "/" } else { path }));
+        path.truncate(parent_len);
+        res
+    }
+
+    fn read_fdt(
+        node: FdtNode<'a>,
+        registry: &dyn PropertyRegistry,
+        path: &mut String,
+        depth: usize,
+    ) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(ErrorKind::ParsingFailed.into());
+        }
+        let inherited = node.inherited();
+        let mut owned = InheritedValues::default();
+
+        // prepare owned
+
+        for prop in node.props() {
+            let prop = prop?;
+            let cells = || {
+                prop.as_u32().ok_or_else(|| {
+                    DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(prop.name())
+                })
+            };
+            match prop.name() {
+                "#address-cells" => owned.address_cells = Some(cells()?),
+                "#size-cells" => owned.size_cells = Some(cells()?),
+                _ => {}
             }
-        } else {
-            Err(DeviceTreeError::ParsingFailed)
         }
+
+        // collect the props and nodes
+
+        let compatible = node
+            .find_prop("compatible")
+            .map(|prop| prop.as_str_list().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut props = Vec::<NodeProperty<'a>>::new();
+        for prop in node.props() {
+            let prop = prop?;
+            props.push(NodeProperty::new(
+                Cow::Borrowed(prop.name()),
+                Cow::Borrowed(prop.raw()),
+                inherited,
+                owned,
+                registry.decoder(&compatible, prop.name()),
+            ));
+        }
+        let mut nodes = Vec::<DeviceTreeNode<'a>>::new();
+        for child in node.children() {
+            nodes.push(DeviceTreeNode::from_fdt_at(
+                child?,
+                registry,
+                path,
+                depth + 1,
+            )?);
+        }
+        Ok(Self {
+            name: Cow::Borrowed(node.name()),
+            inherited,
+            props,
+            nodes,
+        })
     }
 
     /// Get the name of this node
@@ -165,57 +145,184 @@ impl DeviceTreeNode {
         }
     }
 
+    /// Get the phandle other nodes refer to it by, from `phandle` or the legacy `linux,phandle`
+    pub fn phandle(&self) -> Option<u32> {
+        self.prop_u32("phandle")
+            .or_else(|| self.prop_u32("linux,phandle"))
+    }
+
+    /// Whether one of the strings of its `compatible` is `compatible`
+    pub fn is_compatible(&self, compatible: &str) -> bool {
+        self.find_prop("compatible")
+            .is_some_and(|prop| prop.as_str_list().any(|it| it == compatible))
+    }
+
+    /// Whether it is enabled, it has no `status` or it is `okay` or the legacy `ok`
+    pub fn is_available(&self) -> bool {
+        self.find_prop("status").map_or(true, |status| {
+            matches!(status.as_str(), Some("okay" | "ok"))
+        })
+    }
+
+    // a property holding exactly one cell
+    pub(crate) fn prop_u32(&self, name: &str) -> Option<u32> {
+        self.find_prop(name)
+            .and_then(|prop| <[u8; 4]>::try_from(prop.raw()).ok())
+            .map(u32::from_be_bytes)
+    }
+
     /// Get a reference of its owned properties
-    pub fn props(&self) -> &[NodeProperty] {
+    pub fn props(&self) -> &[NodeProperty<'a>] {
         &self.props
     }
 
     /// Get a reference of its owned children
-    pub fn nodes(&self) -> &[DeviceTreeNode] {
+    pub fn nodes(&self) -> &[DeviceTreeNode<'a>] {
         &self.nodes
     }
+
+    /// Get a mutable reference of a child by its name
+    pub fn find_child_mut(&mut self, name: &str) -> Option<&mut DeviceTreeNode<'a>> {
+        self.nodes.iter_mut().find(|node| node.name() == name)
+    }
+
+    /// Change the name of this node, see [DeviceTreeNode::new] for the names which can be written
+    pub fn rename(&mut self, name: impl Into<Cow<'a, str>>) {
+        self.name = name.into();
+    }
+
+    /// Attach a child, replacing the one with the same name if present
+    /// Returns a mutable reference of the attached child for further editing
+    pub fn add_child(&mut self, mut child: DeviceTreeNode<'a>) -> &mut DeviceTreeNode<'a> {
+        child.inherited = self.owned();
+        child.refresh_cells();
+        let index = match self
+            .nodes
+            .iter()
+            .position(|node| node.name() == child.name())
+        {
+            Some(index) => {
+                self.nodes[index] = child;
+                index
+            }
+            None => {
+                self.nodes.push(child);
+                self.nodes.len() - 1
+            }
+        };
+        &mut self.nodes[index]
+    }
+
+    /// Detach a child by its name
+    pub fn remove_child(&mut self, name: &str) -> Option<DeviceTreeNode<'a>> {
+        let index = self.nodes.iter().position(|node| node.name() == name)?;
+        Some(self.nodes.remove(index))
+    }
+
+    /// Set a property from a typed value, replacing the one with the same name if present
+    /// Addresses and sizes are encoded with the `#address-cells` and `#size-cells` in effect
+    pub fn set_prop(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: PropertyValue<'_>,
+    ) -> Result<()> {
+        let name = name.into();
+        let raw_value = value
+            .to_raw(self.inherited, self.owned())
+            .map_err(|err| err.in_property(&name))?;
+        self.set_prop_raw(name, raw_value);
+        Ok(())
+    }
+
+    /// Set a property from its raw bytes, replacing the one with the same name if present
+    /// A name holding `\0` makes [crate::device_tree::DeviceTree::to_bytes] fail
+    pub fn set_prop_raw(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        raw_value: impl Into<Cow<'a, [u8]>>,
+    ) {
+        let name = name.into();
+        let raw_value = raw_value.into();
+        let is_cells = Self::is_cells(&name);
+        if let Some(prop) = self.props.iter_mut().find(|prop| prop.name() == name) {
+            prop.set_raw_value(raw_value);
+        } else {
+            let prop = NodeProperty::new(name, raw_value, self.inherited, self.owned(), None);
+            self.props.push(prop);
+        }
+        if is_cells {
+            self.refresh_cells();
+        }
+    }
+
+    /// Remove a property by its name
+    pub fn remove_prop(&mut self, name: &str) -> Option<NodeProperty<'a>> {
+        let index = self.props.iter().position(|prop| prop.name() == name)?;
+        let prop = self.props.remove(index);
+        if Self::is_cells(name) {
+            self.refresh_cells();
+        }
+        Some(prop)
+    }
+
+    fn is_cells(name: &str) -> bool {
+        name == "#address-cells" || name == "#size-cells"
+    }
+
+    // `#address-cells` and `#size-cells` this node declares for its children
+    fn owned(&self) -> InheritedValues {
+        InheritedValues {
+            address_cells: self.prop_u32("#address-cells"),
+            size_cells: self.prop_u32("#size-cells"),
+        }
+    }
+
+    // hands the cells down again after they have been edited
+    fn refresh_cells(&mut self) {
+        let owned = self.owned();
+        for prop in &mut self.props {
+            prop.inherited = self.inherited;
+            prop.owned = owned;
+        }
+        for node in &mut self.nodes {
+            node.inherited = owned;
+            for prop in &mut node.props {
+                prop.inherited = owned;
+            }
+        }
+    }
 }
 
-impl Display for DeviceTreeNode {
+impl Display for DeviceTreeNode<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        if let Err(err) = writeln!(f, "{} {{", self.name) {
-            return Err(err);
-        }
+        writeln!(f, "{} {{", self.name)?;
         for i in &self.props {
-            if let Err(err) = writeln!(f, "\t{}", i) {
-                return Err(err);
-            }
+            writeln!(f, "\t{}", i)?;
         }
         for i in &self.nodes {
             let mut buffer = String::new();
-            if let Err(err) = write!(buffer, "\t{}", i) {
-                return Err(err);
-            }
+            write!(buffer, "\t{}", i)?;
             let mut first_line = true;
             for j in buffer.split('\n') {
                 if !first_line {
-                    if let Err(err) = write!(f, "\t") {
-                        return Err(err);
-                    }
+                    write!(f, "\t")?;
                 } else {
                     first_line = false;
                 }
-                if let Err(err) = writeln!(f, "{}", j) {
-                    return Err(err);
-                }
+                writeln!(f, "{}", j)?;
             }
         }
         write!(f, "}};")
     }
 }
 
-impl HasNamedChildNode for DeviceTreeNode {
+impl<'a> HasNamedChildNode<'a> for DeviceTreeNode<'a> {
     fn has_children(&self) -> bool {
         !self.nodes().is_empty()
     }
 
-    fn find_child(&self, name: &str) -> Option<&DeviceTreeNode> {
-        let mut option: Option<&DeviceTreeNode> = None;
+    fn find_child(&self, name: &str) -> Option<&DeviceTreeNode<'a>> {
+        let mut option: Option<&DeviceTreeNode<'a>> = None;
         for i in &self.nodes {
             if i.name() == name {
                 option = Some(i);
@@ -226,13 +333,13 @@ impl HasNamedChildNode for DeviceTreeNode {
     }
 }
 
-impl HasNamedProperty for DeviceTreeNode {
+impl<'a> HasNamedProperty<'a> for DeviceTreeNode<'a> {
     fn has_props(&self) -> bool {
         !self.props.is_empty()
     }
 
-    fn find_prop(&self, name: &str) -> Option<&NodeProperty> {
-        let mut option: Option<&NodeProperty> = None;
+    fn find_prop(&self, name: &str) -> Option<&NodeProperty<'a>> {
+        let mut option: Option<&NodeProperty<'a>> = None;
         for i in &self.props {
             if i.name() == name {
                 option = Some(i);
@@ -242,11 +349,7 @@ impl HasNamedProperty for DeviceTreeNode {
         option
     }
 
-    fn of_value(&self, name: &str) -> Option<&PropertyValue> {
-        if let Some(prop) = self.find_prop(name) {
-            Some(prop.value())
-        } else {
-            None
-        }
+    fn of_value(&self, name: &str) -> Option<PropertyValue<'_>> {
+        self.find_prop(name).map(|prop| prop.value())
     }
 }
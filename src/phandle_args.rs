@@ -0,0 +1,149 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{format, string::String, vec::Vec};
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::error::{ErrorKind, Result};
+use crate::node::DeviceTreeNode;
+use crate::traits::HasNamedProperty;
+
+/// An entry of a phandle list such as `clocks` or `gpios`: its provider and the arguments for it
+#[derive(Clone)]
+pub struct PhandleArgs<'b, 'a> {
+    provider: NodeRef<'b, 'a>,
+    args: Vec<u32>,
+}
+
+impl<'b, 'a> PhandleArgs<'b, 'a> {
+    /// Get the node the phandle refers to
+    pub fn provider(&self) -> &NodeRef<'b, 'a> {
+        &self.provider
+    }
+
+    /// Get the arguments, as many as the `#<name>-cells` of the provider
+    pub fn args(&self) -> &[u32] {
+        &self.args
+    }
+}
+
+/// Iterator over the `<&provider arg...>` entries of a phandle list, like `of_parse_phandle_with_args`
+/// A zero phandle is an empty entry which yields `None`, it keeps its place for the `-names`
+/// It stops after the first error
+pub struct PhandleArgsIter<'b, 'a> {
+    tree: &'b DeviceTree<'a>,
+    list: String,
+    cells_name: String,
+    cells: Result<Vec<u32>>,
+    position: usize,
+}
+
+impl<'b, 'a> PhandleArgsIter<'b, 'a> {
+    fn read_entry(&mut self) -> Result<Option<PhandleArgs<'b, 'a>>> {
+        let cells = self.cells.as_ref().map_err(Clone::clone)?;
+        let phandle = cells[self.position];
+        if phandle == 0 {
+            self.position += 1;
+            return Ok(None);
+        }
+        let provider = self.tree.resolve(phandle)?;
+        let count = provider
+            .prop_u32(&self.cells_name)
+            .ok_or(ErrorKind::MissingCellParameter)? as usize;
+        let args = cells
+            .get(self.position + 1..)
+            .and_then(|rest| rest.get(..count))
+            .ok_or(ErrorKind::ParsingFailed)?
+            .to_vec();
+        self.position += 1 + count;
+        Ok(Some(PhandleArgs { provider, args }))
+    }
+}
+
+impl<'b, 'a> Iterator for PhandleArgsIter<'b, 'a> {
+    type Item = Result<Option<PhandleArgs<'b, 'a>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &self.cells {
+            Ok(cells) if self.position >= cells.len() => return None,
+            _ => {}
+        }
+        let res = self.read_entry().map_err(|err| err.in_property(&self.list));
+        if res.is_err() {
+            self.cells = Ok(Vec::new());
+        }
+        Some(res)
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Iterate over the entries of the phandle list `list` of `node`,
+    /// each taking as many arguments as its provider's `cells_name` property says
+    /// Nothing is yielded if `node` has no such list
+    pub fn phandle_args<'b>(
+        &'b self,
+        node: &DeviceTreeNode<'a>,
+        list: &str,
+        cells_name: &str,
+    ) -> PhandleArgsIter<'b, 'a> {
+        PhandleArgsIter {
+            tree: self,
+            list: list.into(),
+            cells_name: cells_name.into(),
+            cells: node
+                .find_prop(list)
+                .map_or(Ok(Vec::new()), |prop| prop.cells()),
+            position: 0,
+        }
+    }
+
+    /// Find the entry of the phandle list `list` of `node` named `name` by its `-names`,
+    /// e.g. `clock-names` for `clocks`, `None` if no entry has that name
+    pub fn phandle_args_by_name<'b>(
+        &'b self,
+        node: &DeviceTreeNode<'a>,
+        list: &str,
+        cells_name: &str,
+        name: &str,
+    ) -> Result<Option<PhandleArgs<'b, 'a>>> {
+        let index = node
+            .find_prop(&names_of(list))
+            .and_then(|names| names.as_str_list().position(|it| it == name));
+        let index = match index {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        // the iterator stops at the first error, which must not pass for a missing entry
+        let mut entries = self.phandle_args(node, list, cells_name);
+        for _ in 0..index {
+            if let Some(Err(err)) = entries.next() {
+                return Err(err);
+            }
+        }
+        entries.next().unwrap_or(Ok(None))
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Iterate over the entries of its phandle list `list`, see [DeviceTree::phandle_args]
+    pub fn phandle_args(&self, list: &str, cells_name: &str) -> PhandleArgsIter<'b, 'a> {
+        self.tree().phandle_args(self.node(), list, cells_name)
+    }
+
+    /// Find the entry of its phandle list `list` named `name`, see [DeviceTree::phandle_args_by_name]
+    pub fn phandle_args_by_name(
+        &self,
+        list: &str,
+        cells_name: &str,
+        name: &str,
+    ) -> Result<Option<PhandleArgs<'b, 'a>>> {
+        self.tree()
+            .phandle_args_by_name(self.node(), list, cells_name, name)
+            .map_err(|err| err.in_node(&self.path()))
+    }
+}
+
+// `clocks` is named by `clock-names`, `dmas` by `dma-names` and so on
+fn names_of(list: &str) -> String {
+    format!("{}-names", list.strip_suffix('s').unwrap_or(list))
+}
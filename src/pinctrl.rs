@@ -0,0 +1,183 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::{format, vec::Vec};
+
+use crate::device_tree::NodeRef;
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::node::DeviceTreeNode;
+use crate::traits::HasNamedProperty;
+
+/// A pin control state of a device: `pinctrl-<id>` named by its entry of `pinctrl-names`
+#[derive(Clone)]
+pub struct PinctrlState<'b, 'a> {
+    id: u32,
+    name: &'b str,
+    configs: Vec<NodeRef<'b, 'a>>,
+}
+
+impl<'b, 'a> PinctrlState<'b, 'a> {
+    /// Get the number of its `pinctrl-<id>` property
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Get its name, the id itself when there is no `pinctrl-names`
+    pub fn name(&self) -> &'b str {
+        self.name
+    }
+
+    /// Get the configuration nodes to apply, in order
+    pub fn configs(&self) -> &[NodeRef<'b, 'a>] {
+        &self.configs
+    }
+}
+
+/// Bias of the generic pin configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bias {
+    /// `bias-disable`
+    Disable,
+    /// `bias-high-impedance`
+    HighImpedance,
+    /// `bias-bus-hold`
+    BusHold,
+    /// `bias-pull-up`, with its resistance in ohms if given
+    PullUp(Option<u32>),
+    /// `bias-pull-down`, with its resistance in ohms if given
+    PullDown(Option<u32>),
+    /// `bias-pull-pin-default`
+    PullPinDefault,
+}
+
+/// The generic pin configuration and multiplexing properties of a configuration node
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinConfig<'b> {
+    /// The `pins` it applies to
+    pub pins: Vec<&'b str>,
+    /// The `groups` it applies to
+    pub groups: Vec<&'b str>,
+    /// The `function` to mux the pins or groups to
+    pub function: Option<&'b str>,
+    /// The `pinmux` cells, each a pin and its function packed as the controller defines
+    pub pinmux: Vec<u32>,
+    /// The bias, the first of the `bias-*` properties
+    pub bias: Option<Bias>,
+    /// The `drive-strength` in mA
+    pub drive_strength: Option<u32>,
+}
+
+impl<'a> DeviceTreeNode<'a> {
+    /// Decode its generic pin configuration, a node `pinctrl-<id>` refers to
+    pub fn pin_config(&self) -> Result<PinConfig<'_>> {
+        let strings = |name| {
+            self.find_prop(name)
+                .map(|prop| prop.as_str_list().collect())
+                .unwrap_or_default()
+        };
+        let pinmux = match self.find_prop("pinmux") {
+            Some(pinmux) => pinmux
+                .cells()
+                .map_err(|err| err.in_property(pinmux.name()))?,
+            None => Vec::new(),
+        };
+        Ok(PinConfig {
+            pins: strings("pins"),
+            groups: strings("groups"),
+            function: self.find_prop("function").and_then(|prop| prop.as_str()),
+            pinmux,
+            bias: self.bias()?,
+            drive_strength: self.optional_u32("drive-strength")?,
+        })
+    }
+
+    fn bias(&self) -> Result<Option<Bias>> {
+        let flags = [
+            ("bias-disable", Bias::Disable),
+            ("bias-high-impedance", Bias::HighImpedance),
+            ("bias-bus-hold", Bias::BusHold),
+            ("bias-pull-pin-default", Bias::PullPinDefault),
+        ];
+        if let Some((_, bias)) = flags
+            .into_iter()
+            .find(|(name, _)| self.find_prop(name).is_some())
+        {
+            return Ok(Some(bias));
+        }
+        // pulls may give their resistance, an empty value or a value of 1 only enables them
+        let ohms = |name| -> Result<Option<Option<u32>>> {
+            match self.find_prop(name) {
+                Some(prop) if prop.raw().is_empty() => Ok(Some(None)),
+                Some(prop) => match prop.as_u32() {
+                    Some(1) => Ok(Some(None)),
+                    Some(ohms) => Ok(Some(Some(ohms))),
+                    None => Err(DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(name)),
+                },
+                None => Ok(None),
+            }
+        };
+        if let Some(ohms) = ohms("bias-pull-up")? {
+            return Ok(Some(Bias::PullUp(ohms)));
+        }
+        Ok(ohms("bias-pull-down")?.map(Bias::PullDown))
+    }
+
+    // a property which is either missing or a single cell
+    fn optional_u32(&self, name: &str) -> Result<Option<u32>> {
+        match self.find_prop(name) {
+            Some(prop) => prop
+                .as_u32()
+                .map(Some)
+                .ok_or_else(|| DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(name)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Resolve its pin control states from `pinctrl-0`, `pinctrl-1` and so on,
+    /// up to the first missing one, named by `pinctrl-names`
+    pub fn pinctrl_states(&self) -> Result<Vec<PinctrlState<'b, 'a>>> {
+        self.read_pinctrl_states()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    /// Find the pin control state named `name`, e.g. `default` or `sleep`
+    pub fn pinctrl_state(&self, name: &str) -> Result<Option<PinctrlState<'b, 'a>>> {
+        Ok(self
+            .pinctrl_states()?
+            .into_iter()
+            .find(|state| state.name() == name))
+    }
+
+    fn read_pinctrl_states(&self) -> Result<Vec<PinctrlState<'b, 'a>>> {
+        let node = self.node();
+        let mut names = node
+            .find_prop("pinctrl-names")
+            .map(|names| names.as_str_list());
+        let mut states = Vec::new();
+        for id in 0.. {
+            let Some(prop) = node.find_prop(&format!("pinctrl-{}", id)) else {
+                break;
+            };
+            let configs = prop
+                .cells()
+                .and_then(|phandles| {
+                    phandles
+                        .into_iter()
+                        // a zero phandle is a placeholder for a state without configuration
+                        .filter(|phandle| *phandle != 0)
+                        .map(|phandle| self.resolve(phandle))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .map_err(|err| err.in_property(prop.name()))?;
+            let name = names.as_mut().and_then(Iterator::next);
+            states.push(PinctrlState {
+                id,
+                name: name.unwrap_or(&prop.name()["pinctrl-".len()..]),
+                configs,
+            });
+        }
+        Ok(states)
+    }
+}
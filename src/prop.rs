@@ -1,24 +1,28 @@
 #[cfg(not(feature = "std"))]
-use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 #[cfg(not(feature = "std"))]
 use core::fmt::{Display, Formatter};
 #[cfg(feature = "std")]
 use std::fmt::{Display, Formatter};
 #[cfg(feature = "std")]
-use std::{borrow::ToOwned, format, string::String, vec::Vec};
+use std::{borrow::Cow, format, string::String, vec::Vec};
 
 use crate::byte_utils::{
-    align_block, align_size, locate_block, read_aligned_be_big_number, read_aligned_be_number,
-    read_aligned_be_u32, read_aligned_sized_strings, read_name, BLOCK_SIZE,
+    align_size, read_aligned_be_big_number, read_aligned_be_number, read_aligned_be_u32,
+    read_aligned_sized_strings, write_be_number, BLOCK_SIZE,
 };
-use crate::device_tree::InheritedValues;
-use crate::error::DeviceTreeError::{self, NotEnoughLength, ParsingFailed};
+use crate::device_tree::DeviceTree;
+use crate::error::ErrorKind::{self, EncodingFailed, MissingCellParameter, ParsingFailed};
 use crate::error::Result;
-use crate::header::DeviceTreeHeader;
+use crate::node::DeviceTreeNode;
+use crate::reg::RegEntry;
+use crate::schema::{Decoder, PropertyContext};
+use crate::walker::{Cells, FdtProperty, InheritedValues, StrList};
 
 /// Presenting a variety of values that a [NodeProperty] can hold
+/// Strings are borrowed from the raw value they were decoded from
 #[derive(Debug)]
-pub enum PropertyValue {
+pub enum PropertyValue<'a> {
     /// Empty value
     None,
     /// Single integer
@@ -29,14 +33,16 @@ pub enum PropertyValue {
     /// A pointer referenced by `<specifier>-parent`
     PHandle(u32),
     /// Single string
-    String(String),
+    String(&'a str),
     /// A list of strings
-    Strings(Vec<String>),
+    Strings(Vec<&'a str>),
     // address, size, size with 0 no size
     /// An address with it's length(size)
     Address(u64, u64),
     /// A list of addresses
     Addresses(Vec<(u64, u64)>),
+    /// `reg` entries which do not fit [PropertyValue::Address], wider than 2 cells or without sizes
+    Reg(Vec<RegEntry>),
     // child-bus-address, parent-bus-address, length
     /// A arbitrary number of addresses
     Ranges(Vec<(u128, u64, u64)>),
@@ -44,7 +50,7 @@ pub enum PropertyValue {
     Unknown,
 }
 
-impl Display for PropertyValue {
+impl Display for PropertyValue<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             PropertyValue::None => write!(f, ""),
@@ -69,6 +75,17 @@ impl Display for PropertyValue {
                     .collect::<Vec<String>>()
                     .join(" ")
             ),
+            PropertyValue::Reg(it) => write!(
+                f,
+                "<{}>",
+                it.iter()
+                    .map(|entry| match entry.size() {
+                        Some(size) => format!("{:#x} {:#x}", entry.address(), size),
+                        None => format!("{:#x}", entry.address()),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
             PropertyValue::Ranges(it) => write!(
                 f,
                 "<{}>",
@@ -85,177 +102,197 @@ impl Display for PropertyValue {
     }
 }
 
-/// A property of [crate::node::DeviceTreeNode]
-pub struct NodeProperty {
-    pub(crate) block_count: usize,
-    name: String,
-    value: PropertyValue,
-}
-
-// it wont create value, node does
-impl NodeProperty {
-    pub(crate) fn read_meta(
-        data: &[u8],
-        header: &DeviceTreeHeader,
-        block_start: usize,
-    ) -> Option<(String, u32, usize)> {
-        if let Some(prop_val_size) = read_aligned_be_u32(data, block_start + 1) {
-            if let Some(name_offset) = read_aligned_be_u32(data, block_start + 2) {
-                if let Some(name) = read_name(data, (header.off_dt_strings + name_offset) as usize)
-                {
-                    Some((
-                        name.to_owned(),
-                        prop_val_size,
-                        if prop_val_size > 0 {
-                            3 + align_size(prop_val_size as usize)
-                        } else {
-                            3
-                        },
-                    ))
-                } else {
-                    None
+impl PropertyValue<'_> {
+    // the reverse of parse_value, the cells decide how wide addresses and sizes are
+    pub(crate) fn to_raw(
+        &self,
+        inherited: InheritedValues,
+        owned: InheritedValues,
+    ) -> Result<Vec<u8>> {
+        let mut raw = Vec::<u8>::new();
+        match self {
+            PropertyValue::None | PropertyValue::Unknown => {}
+            PropertyValue::Integer(it) => {
+                let cells = if *it > u32::MAX as u64 { 2 } else { 1 };
+                write_be_number(&mut raw, *it as u128, cells).ok_or(EncodingFailed)?;
+            }
+            PropertyValue::Integers(it) => {
+                for i in it {
+                    write_be_number(&mut raw, *i as u128, 1).ok_or(EncodingFailed)?;
                 }
-            } else {
-                None
             }
-        } else {
-            None
-        }
-    }
-    pub(crate) fn from_meta(
-        data: &[u8],
-        meta: (String, u32, usize),
-        block_start: usize,
-        inherited: &InheritedValues,
-        owned: &InheritedValues,
-    ) -> Result<NodeProperty> {
-        let value_index = block_start + 3;
-        // standard properties
-        if meta.1 > 0 {
-            let raw_value =
-                &data[locate_block(value_index)..(locate_block(value_index) + meta.1 as usize)];
-            match NodeProperty::parse_value(raw_value, &meta.0, inherited, owned) {
-                Ok(value) => {
-                    return Ok(Self {
-                        block_count: meta.2,
-                        name: meta.0,
-                        value,
-                    })
+            PropertyValue::PHandle(it) => raw.extend_from_slice(&it.to_be_bytes()),
+            PropertyValue::String(it) => write_string(&mut raw, it)?,
+            PropertyValue::Strings(it) => {
+                for i in it {
+                    write_string(&mut raw, i)?;
+                }
+            }
+            PropertyValue::Address(address, size) => {
+                write_address(&mut raw, *address, *size, inherited)?
+            }
+            PropertyValue::Addresses(it) => {
+                for (address, size) in it {
+                    write_address(&mut raw, *address, *size, inherited)?;
+                }
+            }
+            PropertyValue::Reg(it) => {
+                let address_cells = inherited.address_cells.ok_or(MissingCellParameter)? as usize;
+                let size_cells = inherited.size_cells.ok_or(MissingCellParameter)? as usize;
+                for entry in it {
+                    write_be_number(&mut raw, entry.address(), address_cells)
+                        .ok_or(EncodingFailed)?;
+                    match (entry.size(), size_cells) {
+                        (None, 0) => {}
+                        (Some(size), 1..) => write_be_number(&mut raw, size as u128, size_cells)
+                            .ok_or(EncodingFailed)?,
+                        _ => return Err(EncodingFailed.into()),
+                    }
+                }
+            }
+            PropertyValue::Ranges(it) => {
+                let child_cells = owned.address_cells.ok_or(MissingCellParameter)? as usize;
+                let parent_cells = inherited.address_cells.ok_or(MissingCellParameter)? as usize;
+                let size_cells = owned.size_cells.ok_or(MissingCellParameter)? as usize;
+                for (child, parent, length) in it {
+                    write_be_number(&mut raw, *child, child_cells).ok_or(EncodingFailed)?;
+                    write_be_number(&mut raw, *parent as u128, parent_cells)
+                        .ok_or(EncodingFailed)?;
+                    write_be_number(&mut raw, *length as u128, size_cells).ok_or(EncodingFailed)?;
                 }
-                Err(err) => Err(err),
             }
-        } else {
-            Ok(Self {
-                block_count: 3,
-                name: meta.0,
-                value: PropertyValue::None,
-            })
         }
+        Ok(raw)
+    }
+}
+
+fn write_string(raw: &mut Vec<u8>, value: &str) -> Result<()> {
+    if value.contains('\0') {
+        return Err(EncodingFailed.into());
     }
-    pub(crate) fn from_bytes(
-        data: &[u8],
-        header: &DeviceTreeHeader,
-        start: usize,
-        inherited: &InheritedValues,
-        owned: &InheritedValues,
-    ) -> Result<NodeProperty> {
-        let block_start = align_block(start);
-        if let Some(meta) = Self::read_meta(data, header, block_start) {
-            Self::from_meta(data, meta, block_start, inherited, owned)
-        } else {
-            Err(NotEnoughLength)
+    raw.extend_from_slice(value.as_bytes());
+    raw.push(0);
+    Ok(())
+}
+
+fn write_address(raw: &mut Vec<u8>, address: u64, size: u64, cells: InheritedValues) -> Result<()> {
+    let address_cells = cells.address_cells.ok_or(MissingCellParameter)? as usize;
+    let size_cells = cells.size_cells.ok_or(MissingCellParameter)? as usize;
+    write_be_number(raw, address as u128, address_cells).ok_or(EncodingFailed)?;
+    write_be_number(raw, size as u128, size_cells).ok_or(EncodingFailed.into())
+}
+
+/// A property of [crate::node::DeviceTreeNode]
+/// Holds its name and raw value, borrowed from the blob unless it has been edited,
+/// the typed value is decoded on demand
+pub struct NodeProperty<'a> {
+    name: Cow<'a, str>,
+    raw_value: Cow<'a, [u8]>,
+    pub(crate) inherited: InheritedValues,
+    pub(crate) owned: InheritedValues,
+    // picked by the registry the tree was parsed with, the built-in rules without one
+    decoder: Option<Decoder>,
+}
+
+// it wont create value, node does
+impl<'a> NodeProperty<'a> {
+    pub(crate) fn new(
+        name: Cow<'a, str>,
+        raw_value: Cow<'a, [u8]>,
+        inherited: InheritedValues,
+        owned: InheritedValues,
+        decoder: Option<Decoder>,
+    ) -> Self {
+        Self {
+            name,
+            raw_value,
+            inherited,
+            owned,
+            decoder,
         }
     }
 
-    pub(crate) fn parse_value(
-        raw_value: &[u8],
+    pub(crate) fn parse_value<'v>(
+        raw_value: &'v [u8],
         name: &str,
-        inherited: &InheritedValues,
-        owned: &InheritedValues,
-    ) -> Result<PropertyValue> {
+        inherited: InheritedValues,
+        owned: InheritedValues,
+    ) -> Result<PropertyValue<'v>> {
+        if raw_value.is_empty() {
+            return Ok(PropertyValue::None);
+        }
         match name {
             "compatible" | "model" | "status" => {
-                if let Some(strs) = read_aligned_sized_strings(raw_value, 0, raw_value.len()) {
-                    if strs.len() > 1 {
-                        Ok(PropertyValue::Strings(
-                            strs.into_iter().map(|s| s.to_owned()).collect(),
-                        ))
-                    } else {
-                        Ok(PropertyValue::String(strs[0].to_owned()))
-                    }
-                } else {
-                    Err(ParsingFailed)
+                match read_aligned_sized_strings(raw_value, 0, raw_value.len()) {
+                    Some(strs) if strs.len() > 1 => Ok(PropertyValue::Strings(strs)),
+                    Some(strs) if strs.len() == 1 => Ok(PropertyValue::String(strs[0])),
+                    _ => Err(ParsingFailed.into()),
                 }
             }
             "phandle" | "virtual-reg" => {
                 if let Some(int) = read_aligned_be_u32(raw_value, 0) {
                     Ok(PropertyValue::Integer(int as u64))
                 } else {
-                    Err(ParsingFailed)
+                    Err(ParsingFailed.into())
                 }
             }
             "reg" => {
-                let address_cells = match inherited.find("#address-cells") {
-                    Some(v) => v as usize,
-                    _ => return Err(DeviceTreeError::MissingCellParameter),
-                };
-                let size_cells = match inherited.find("#size-cells") {
-                    Some(v) => v as usize,
-                    _ => return Err(DeviceTreeError::MissingCellParameter),
-                };
-
-                let group_size = align_size(raw_value.len()) / (address_cells + size_cells);
-                if group_size > 1 {
-                    let mut regs = Vec::<(u64, u64)>::new();
-                    for i in 0..group_size {
-                        let group_index = i * (address_cells + size_cells);
-                        let res = (
-                            read_aligned_be_number(raw_value, group_index, address_cells).unwrap(),
-                            read_aligned_be_number(
-                                raw_value,
-                                group_index + address_cells,
-                                size_cells,
-                            )
-                            .unwrap(),
-                        );
-                        regs.push(res);
-                    }
-                    Ok(PropertyValue::Addresses(regs))
+                let entries = Self::parse_reg(raw_value, inherited)?;
+                if inherited.address_cells > Some(2) || inherited.size_cells == Some(0) {
+                    return Ok(PropertyValue::Reg(entries));
+                }
+                let mut regs = Vec::<(u64, u64)>::new();
+                for entry in entries {
+                    let address = u64::try_from(entry.address()).map_err(|_| ParsingFailed)?;
+                    regs.push((address, entry.size().ok_or(ParsingFailed)?));
+                }
+                if regs.len() == 1 {
+                    Ok(PropertyValue::Address(regs[0].0, regs[0].1))
                 } else {
-                    Ok(PropertyValue::Address(
-                        read_aligned_be_number(raw_value, 0, address_cells).unwrap(),
-                        read_aligned_be_number(raw_value, address_cells, size_cells).unwrap(),
-                    ))
+                    Ok(PropertyValue::Addresses(regs))
                 }
             }
             "ranges" | "dma-ranges" => {
-                let child_cells = match owned.find("#address-cells") {
+                let child_cells = match owned.address_cells {
                     Some(v) => v as usize,
-                    _ => return Err(DeviceTreeError::MissingCellParameter),
+                    _ => return Err(ErrorKind::MissingCellParameter.into()),
                 };
-                let parent_cells = match inherited.find("#address-cells") {
+                let parent_cells = match inherited.address_cells {
                     Some(v) => v as usize,
-                    _ => return Err(DeviceTreeError::MissingCellParameter),
+                    _ => return Err(ErrorKind::MissingCellParameter.into()),
                 };
-                let size_cells = match owned.find("#size-cells") {
+                let size_cells = match owned.size_cells {
                     Some(v) => v as usize,
-                    _ => return Err(DeviceTreeError::MissingCellParameter),
+                    _ => return Err(ErrorKind::MissingCellParameter.into()),
                 };
+                if child_cells > 4 || parent_cells > 2 || size_cells > 2 {
+                    return Err(ParsingFailed.into());
+                }
                 let single_size = child_cells + parent_cells + size_cells;
+                if single_size == 0 {
+                    return Err(ParsingFailed.into());
+                }
+                // a row cut short is not dropped, it is a malformed value
+                if raw_value.len() % BLOCK_SIZE != 0
+                    || align_size(raw_value.len()) % single_size != 0
+                {
+                    return Err(ParsingFailed.into());
+                }
                 let group_size = align_size(raw_value.len()) / single_size;
                 let mut rags = Vec::<(u128, u64, u64)>::new();
                 for i in 0..group_size {
                     let group_index = i * single_size;
                     let res = (
-                        read_aligned_be_big_number(raw_value, group_index, child_cells).unwrap(),
+                        read_aligned_be_big_number(raw_value, group_index, child_cells)
+                            .ok_or(ParsingFailed)?,
                         read_aligned_be_number(raw_value, group_index + child_cells, parent_cells)
-                            .unwrap(),
+                            .ok_or(ParsingFailed)?,
                         read_aligned_be_number(
                             raw_value,
                             group_index + child_cells + parent_cells,
                             size_cells,
                         )
-                        .unwrap(),
+                        .ok_or(ParsingFailed)?,
                     );
                     rags.push(res);
                 }
@@ -267,43 +304,37 @@ impl NodeProperty {
             // 1 => low to high & edge trigger; 2 => high to low & edge trigger
             // 3 => active high & level-sensitive 4 => active low & level-sensitive
             // interrupt-extends => (PHandleRef, Integers)s
-            // TODO: interrupt-parent is not inherited
+            // the inherited interrupt parent is resolved by `NodeRef::interrupt_parent`
             x if x.ends_with("-parent") => {
                 if let Some(int) = read_aligned_be_u32(raw_value, 0) {
                     Ok(PropertyValue::PHandle(int))
                 } else {
-                    Err(ParsingFailed)
+                    Err(ParsingFailed.into())
                 }
             }
             // nexus node's property
-            x if x.starts_with("#") && x.ends_with("cells") => {
+            x if x.starts_with('#') && x.ends_with("cells") => {
                 if let Some(int) = read_aligned_be_u32(raw_value, 0) {
                     Ok(PropertyValue::Integer(int as u64))
                 } else {
-                    Err(ParsingFailed)
+                    Err(ParsingFailed.into())
                 }
             }
             _ => {
-                let a = raw_value.len() as usize % BLOCK_SIZE == 0; // str or int | must str
+                let a = raw_value.len() % BLOCK_SIZE == 0; // str or int | must str
                 let b = raw_value[0] != b'\0'
-                    && raw_value[(raw_value.len() - 1) as usize] == b'\0'
+                    && raw_value[raw_value.len() - 1] == b'\0'
                     && raw_value.is_ascii(); // A then must str
-                if !a || a && b {
+                if !a || b {
                     // must be str
-                    if let Some(strs) = read_aligned_sized_strings(raw_value, 0, raw_value.len()) {
-                        if strs.len() > 1 {
-                            Ok(PropertyValue::Strings(
-                                strs.into_iter().map(|s| s.to_owned()).collect(),
-                            ))
-                        } else {
-                            Ok(PropertyValue::String(strs[0].to_owned()))
-                        }
-                    } else {
-                        Err(ParsingFailed)
+                    match read_aligned_sized_strings(raw_value, 0, raw_value.len()) {
+                        Some(strs) if strs.len() > 1 => Ok(PropertyValue::Strings(strs)),
+                        Some(strs) if strs.len() == 1 => Ok(PropertyValue::String(strs[0])),
+                        _ => Err(ParsingFailed.into()),
                     }
                 } else {
                     // must be integer(s)
-                    let size = raw_value.len() as usize / BLOCK_SIZE;
+                    let size = raw_value.len() / BLOCK_SIZE;
                     if size > 1 {
                         // integers
                         // TODO: interrupt-cells = 2
@@ -312,13 +343,13 @@ impl NodeProperty {
                             if let Some(num) = read_aligned_be_u32(raw_value, i) {
                                 res.push(num as u64);
                             } else {
-                                return Err(ParsingFailed);
+                                return Err(ParsingFailed.into());
                             }
                         }
                         Ok(PropertyValue::Integers(res))
                     } else {
                         Ok(PropertyValue::Integer(
-                            read_aligned_be_u32(raw_value, 0).unwrap() as u64,
+                            read_aligned_be_u32(raw_value, 0).ok_or(ParsingFailed)? as u64,
                         ))
                     }
                 }
@@ -326,20 +357,127 @@ impl NodeProperty {
         }
     }
 
+    fn parse_reg(raw_value: &[u8], inherited: InheritedValues) -> Result<Vec<RegEntry>> {
+        let address_cells = inherited.address_cells.ok_or(MissingCellParameter)? as usize;
+        let size_cells = inherited.size_cells.ok_or(MissingCellParameter)? as usize;
+        let entry_size = address_cells + size_cells;
+        if entry_size == 0
+            || address_cells > 4
+            || size_cells > 2
+            || raw_value.len() % (entry_size * BLOCK_SIZE) != 0
+        {
+            return Err(ParsingFailed.into());
+        }
+        let mut entries = Vec::new();
+        for index in (0..align_size(raw_value.len())).step_by(entry_size) {
+            let address =
+                read_aligned_be_big_number(raw_value, index, address_cells).ok_or(ParsingFailed)?;
+            let size = match size_cells {
+                0 => None,
+                _ => Some(
+                    read_aligned_be_number(raw_value, index + address_cells, size_cells)
+                        .ok_or(ParsingFailed)?,
+                ),
+            };
+            entries.push(RegEntry::new(address, address_cells as u32, size));
+        }
+        Ok(entries)
+    }
+
+    /// Decode it as `reg` entries with the `#address-cells` and `#size-cells` of its bus
+    pub fn reg_entries(&self) -> Result<Vec<RegEntry>> {
+        Self::parse_reg(&self.raw_value, self.inherited).map_err(|err| err.in_property(&self.name))
+    }
+
     /// Get its name
     pub fn name(&self) -> &str {
         &self.name
     }
 
-    /// Get its value
-    pub fn value(&self) -> &PropertyValue {
-        &self.value
+    /// Get its value as it is stored in the blob, whatever type [NodeProperty::value] guesses
+    pub fn raw(&self) -> &[u8] {
+        &self.raw_value
+    }
+
+    /// Read its value as a single big-endian u32
+    pub fn as_u32(&self) -> Option<u32> {
+        self.view().as_u32()
+    }
+
+    /// Read its value as a single big-endian u64, a single u32 is also accepted
+    pub fn as_u64(&self) -> Option<u64> {
+        self.view().as_u64()
+    }
+
+    /// Read its value as a single zero-terminated string
+    pub fn as_str(&self) -> Option<&str> {
+        self.view().as_str()
+    }
+
+    /// Read its value as a list of zero-terminated strings
+    pub fn as_str_list(&self) -> StrList<'_> {
+        self.view().as_str_list()
+    }
+
+    /// Read its value as a list of big-endian u32 cells, `None` if its length is not a multiple of 4
+    pub fn as_cells(&self) -> Option<Cells<'_>> {
+        self.view().as_cells()
+    }
+
+    /// Read its value as a bytestring, which is the raw value itself
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw_value
+    }
+
+    // the accessors of the walker, reading the value in place
+    fn view(&self) -> FdtProperty<'_> {
+        FdtProperty::new(&self.name, &self.raw_value)
+    }
+
+    pub(crate) fn set_raw_value(&mut self, raw_value: Cow<'a, [u8]>) {
+        self.raw_value = raw_value;
+    }
+
+    // its value as big-endian cells
+    pub(crate) fn cells(&self) -> Result<Vec<u32>> {
+        if self.raw_value.len() % BLOCK_SIZE != 0 {
+            return Err(ParsingFailed.into());
+        }
+        Ok(self
+            .raw_value
+            .chunks_exact(BLOCK_SIZE)
+            .map(|cell| u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]]))
+            .collect())
+    }
+
+    /// Find the node its value refers to, when the value is a single phandle cell
+    pub fn resolve_phandle<'t>(&self, tree: &'t DeviceTree<'a>) -> Option<&'t DeviceTreeNode<'a>> {
+        let phandle = <[u8; 4]>::try_from(self.raw()).ok()?;
+        tree.find_by_phandle(u32::from_be_bytes(phandle))
+    }
+
+    /// Decode its value, [PropertyValue::Unknown] is returned if the raw value cannot be parsed
+    /// The decoder is the one its registry picked, see [crate::schema::PropertyRegistry]
+    pub fn value(&self) -> PropertyValue<'_> {
+        self.try_value().unwrap_or(PropertyValue::Unknown)
+    }
+
+    /// Decode its value, failing with an error naming the property if the raw value cannot be parsed
+    pub fn try_value(&self) -> Result<PropertyValue<'_>> {
+        match self.decoder {
+            Some(decoder) => decoder(
+                &self.raw_value,
+                &PropertyContext::new(&self.name, self.inherited, self.owned),
+            ),
+            None => Self::parse_value(&self.raw_value, &self.name, self.inherited, self.owned),
+        }
+        .map_err(|err| err.in_property(self.name()))
     }
 }
 
-impl Display for NodeProperty {
+impl Display for NodeProperty<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        match &self.value {
+        match &self.value() {
             PropertyValue::Unknown | PropertyValue::None => write!(f, "{};", self.name),
             other => write!(f, "{} = {};", self.name, other),
         }
@@ -0,0 +1,103 @@
+/// An entry of a `reg` property, its address is as wide as the `#address-cells` of the bus
+/// and it has no size when the bus sets `#size-cells = <0>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegEntry {
+    address: u128,
+    address_cells: u32,
+    size: Option<u64>,
+}
+
+impl RegEntry {
+    /// Creates an entry of an address taking `address_cells` cells
+    pub const fn new(address: u128, address_cells: u32, size: Option<u64>) -> Self {
+        Self {
+            address,
+            address_cells,
+            size,
+        }
+    }
+
+    /// Get the address, the first cell being the most significant
+    pub fn address(&self) -> u128 {
+        self.address
+    }
+
+    /// Get the number of cells the address takes
+    pub fn address_cells(&self) -> u32 {
+        self.address_cells
+    }
+
+    /// Get the address as cells, the first one being the most significant
+    pub fn cells(&self) -> impl Iterator<Item = u32> {
+        let address = self.address;
+        (0..self.address_cells)
+            .rev()
+            .map(move |i| (address >> (32 * i)) as u32)
+    }
+
+    /// Get the length in bytes, `None` if the bus has no sizes
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Decode a three cells PCI address, whose first cell `phys.hi` describes the function
+    pub fn pci(&self) -> Option<PciAddress> {
+        if self.address_cells != 3 {
+            return None;
+        }
+        let hi = (self.address >> 64) as u32;
+        let space = match (hi >> 24) & 0b11 {
+            0b00 => PciSpace::Configuration,
+            0b01 => PciSpace::Io,
+            0b10 => PciSpace::Memory32,
+            _ => PciSpace::Memory64,
+        };
+        Some(PciAddress {
+            relocatable: hi & (1 << 31) == 0,
+            prefetchable: hi & (1 << 30) != 0,
+            aliased: hi & (1 << 29) != 0,
+            space,
+            bus: (hi >> 16) as u8,
+            device: ((hi >> 11) & 0x1f) as u8,
+            function: ((hi >> 8) & 0b111) as u8,
+            register: hi as u8,
+            address: self.address as u64,
+        })
+    }
+}
+
+/// Address space of a PCI address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PciSpace {
+    /// Configuration space
+    Configuration,
+    /// I/O space
+    Io,
+    /// 32 bits memory space
+    Memory32,
+    /// 64 bits memory space
+    Memory64,
+}
+
+/// A PCI address decoded from its `phys.hi`, `phys.mid` and `phys.lo` cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PciAddress {
+    /// The address may be relocated, the `n` bit is clear
+    pub relocatable: bool,
+    /// The region is prefetchable, the `p` bit
+    pub prefetchable: bool,
+    /// The address is aliased below 1M or 64K, the `t` bit
+    pub aliased: bool,
+    /// The address space, the `ss` bits
+    pub space: PciSpace,
+    /// The bus number
+    pub bus: u8,
+    /// The device number
+    pub device: u8,
+    /// The function number
+    pub function: u8,
+    /// The configuration register number
+    pub register: u8,
+    /// The 64 bits address of `phys.mid` and `phys.lo`
+    pub address: u64,
+}
@@ -0,0 +1,86 @@
+use crate::byte_utils::{align_size, read_aligned_be_number};
+use crate::error::{ErrorKind, Result};
+
+const ENTRY_SIZE: usize = 16;
+
+/// A range of physical memory reserved by the memory reservation block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReservation {
+    address: u64,
+    size: u64,
+}
+
+impl MemoryReservation {
+    /// Creates a reserved range starting at `address` of `size` bytes
+    pub const fn new(address: u64, size: u64) -> Self {
+        Self { address, size }
+    }
+
+    /// Get the physical address where the range starts
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get the length of the range in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Iterator for the `(address, size)` entries of the memory reservation block
+/// It stops after the terminating zero entry or the first error
+pub struct MemoryReservations<'a> {
+    data: &'a [u8],
+    offset: usize,
+    total_size: usize,
+    finished: bool,
+}
+
+impl<'a> MemoryReservations<'a> {
+    pub(crate) fn new(data: &'a [u8], offset: usize, total_size: usize) -> Self {
+        Self {
+            data,
+            offset,
+            total_size,
+            finished: false,
+        }
+    }
+
+    fn read_entry(&mut self) -> Result<Option<MemoryReservation>> {
+        if self.offset % 8 != 0 {
+            return Err(ErrorKind::ParsingFailed.into());
+        }
+        if self.offset + ENTRY_SIZE > self.total_size.min(self.data.len()) {
+            return Err(ErrorKind::NotEnoughLength.into());
+        }
+        let index = align_size(self.offset);
+        let address =
+            read_aligned_be_number(self.data, index, 2).ok_or(ErrorKind::NotEnoughLength)?;
+        let size =
+            read_aligned_be_number(self.data, index + 2, 2).ok_or(ErrorKind::NotEnoughLength)?;
+        self.offset += ENTRY_SIZE;
+        if address == 0 && size == 0 {
+            Ok(None)
+        } else if address.checked_add(size).is_none() {
+            Err(ErrorKind::ParsingFailed.into())
+        } else {
+            Ok(Some(MemoryReservation { address, size }))
+        }
+    }
+}
+
+impl Iterator for MemoryReservations<'_> {
+    type Item = Result<MemoryReservation>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let offset = self.offset;
+        let res = self.read_entry().map_err(|err| err.at(offset)).transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
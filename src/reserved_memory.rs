@@ -0,0 +1,225 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::prop::NodeProperty;
+use crate::traits::HasNamedProperty;
+
+/// Where a reserved region lies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    /// At the `(address, size)` pairs of its `reg`
+    Static(Vec<(u64, u64)>),
+    /// Anywhere the memory manager allocates it
+    Dynamic {
+        /// The `size` to allocate
+        size: u64,
+        /// The `alignment` of its address
+        alignment: Option<u64>,
+        /// The `(address, size)` pairs of `alloc-ranges` it has to lie within, anywhere if empty
+        alloc_ranges: Vec<(u64, u64)>,
+    },
+}
+
+/// A child of `/reserved-memory`
+#[derive(Clone)]
+pub struct ReservedRegion<'b, 'a> {
+    node: NodeRef<'b, 'a>,
+    placement: Placement,
+}
+
+impl<'b, 'a> ReservedRegion<'b, 'a> {
+    /// Get the region node
+    pub fn node(&self) -> &NodeRef<'b, 'a> {
+        &self.node
+    }
+
+    /// Get where it lies
+    pub fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    /// The region must not be mapped by the operating system, `no-map`
+    pub fn no_map(&self) -> bool {
+        self.node.find_prop("no-map").is_some()
+    }
+
+    /// The operating system may use the region as long as its driver can take it back, `reusable`
+    pub fn reusable(&self) -> bool {
+        self.node.find_prop("reusable").is_some()
+    }
+
+    /// The region is a pool for DMA allocations, `compatible = "shared-dma-pool"`
+    pub fn shared_dma_pool(&self) -> bool {
+        self.node.is_compatible("shared-dma-pool")
+    }
+
+    /// The region is the default pool of the devices without `memory-region`,
+    /// `linux,cma-default` or `linux,dma-default`
+    pub fn default_pool(&self) -> bool {
+        self.node.find_prop("linux,cma-default").is_some()
+            || self.node.find_prop("linux,dma-default").is_some()
+    }
+
+    fn from_node(node: NodeRef<'b, 'a>) -> Result<Self> {
+        let pairs = |prop: &NodeProperty| -> Result<Vec<(u64, u64)>> {
+            prop.reg_entries()?
+                .into_iter()
+                .map(|entry| {
+                    let address = u64::try_from(entry.address()).ok();
+                    address.zip(entry.size()).ok_or_else(|| {
+                        DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(prop.name())
+                    })
+                })
+                .collect()
+        };
+        let number = |name: &str| -> Result<Option<u64>> {
+            match node.find_prop(name) {
+                Some(prop) => prop.as_u64().map(Some).ok_or_else(|| {
+                    DeviceTreeError::from(ErrorKind::ParsingFailed).in_property(name)
+                }),
+                None => Ok(None),
+            }
+        };
+        let placement = match (node.find_prop("reg"), number("size")?) {
+            (Some(reg), _) => Placement::Static(pairs(reg)?),
+            (None, Some(size)) => Placement::Dynamic {
+                size,
+                alignment: number("alignment")?,
+                alloc_ranges: match node.find_prop("alloc-ranges") {
+                    Some(ranges) => pairs(ranges)?,
+                    None => Vec::new(),
+                },
+            },
+            (None, None) => return Err(ErrorKind::MissingCellParameter.into()),
+        };
+        Ok(Self { node, placement })
+    }
+}
+
+/// The reserved regions of a tree, the children of `/reserved-memory` and the memory reservation block
+#[derive(Clone)]
+pub struct ReservedMemory<'b, 'a> {
+    tree: &'b DeviceTree<'a>,
+    regions: Vec<ReservedRegion<'b, 'a>>,
+    errors: Vec<DeviceTreeError>,
+}
+
+impl<'b, 'a> ReservedMemory<'b, 'a> {
+    /// Get the available children of `/reserved-memory`
+    pub fn regions(&self) -> &[ReservedRegion<'b, 'a>] {
+        &self.regions
+    }
+
+    /// Get why the children left out of [ReservedMemory::regions] could not be read,
+    /// each error carries the path of its node
+    /// Whoever carves memory out of the regions must give up if there is any, as they may be `no-map`
+    pub fn errors(&self) -> &[DeviceTreeError] {
+        &self.errors
+    }
+
+    /// Find a region by its node name
+    pub fn find(&self, name: &str) -> Option<&ReservedRegion<'b, 'a>> {
+        self.regions
+            .iter()
+            .find(|region| region.node.name() == name)
+    }
+
+    /// Get the regions allocated at run time
+    pub fn dynamic_regions(&self) -> impl Iterator<Item = &ReservedRegion<'b, 'a>> {
+        self.regions
+            .iter()
+            .filter(|region| matches!(region.placement, Placement::Dynamic { .. }))
+    }
+
+    /// Get the `(address, size)` pairs reserved at fixed addresses, by the header or by static regions,
+    /// sorted with the overlapping and adjacent ones merged
+    /// Fails with the first of [ReservedMemory::errors], an unreadable region may lie anywhere
+    pub fn static_ranges(&self) -> Result<Vec<(u64, u64)>> {
+        if let Some(err) = self.errors.first() {
+            return Err(err.clone());
+        }
+        let mut ranges = self
+            .tree
+            .memory_reservations()
+            .map(|reservation| (reservation.address(), reservation.size()))
+            .collect::<Vec<_>>();
+        for region in &self.regions {
+            if let Placement::Static(reg) = &region.placement {
+                ranges.extend_from_slice(reg);
+            }
+        }
+        ranges.retain(|(_, size)| *size != 0);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u64, u64)> = Vec::new();
+        for (address, size) in ranges {
+            let end = address.saturating_add(size);
+            match merged.last_mut() {
+                Some((last, last_size)) if address <= last.saturating_add(*last_size) => {
+                    *last_size = end.max(last.saturating_add(*last_size)) - *last;
+                }
+                _ => merged.push((address, end - address)),
+            }
+        }
+        Ok(merged)
+    }
+}
+
+impl<'a> DeviceTree<'a> {
+    /// Read `/reserved-memory`, a tree without it only has the memory reservation block
+    /// Children with `status` other than `okay` are left out, so are those which cannot be read,
+    /// they are reported by [ReservedMemory::errors] instead
+    pub fn reserved_memory(&self) -> Result<ReservedMemory<'_, 'a>> {
+        let mut regions = Vec::new();
+        let mut errors = Vec::new();
+        if let Some(parent) = self.find_node("/reserved-memory") {
+            let parent = self.node_ref(parent).ok_or(ErrorKind::ParsingFailed)?;
+            for node in parent.children().filter(|node| node.is_available()) {
+                let path = node.path();
+                match ReservedRegion::from_node(node) {
+                    Ok(region) => regions.push(region),
+                    Err(err) => errors.push(err.in_node(&path)),
+                }
+            }
+        }
+        Ok(ReservedMemory {
+            tree: self,
+            regions,
+            errors,
+        })
+    }
+}
+
+impl<'b, 'a> NodeRef<'b, 'a> {
+    /// Resolve its `memory-region` phandles into the reserved regions it uses
+    pub fn memory_regions(&self) -> Result<Vec<ReservedRegion<'b, 'a>>> {
+        self.read_memory_regions()
+            .map_err(|err| err.in_node(&self.path()))
+    }
+
+    fn read_memory_regions(&self) -> Result<Vec<ReservedRegion<'b, 'a>>> {
+        let regions = match self.find_prop("memory-region") {
+            Some(regions) => regions,
+            None => return Ok(Vec::new()),
+        };
+        regions
+            .cells()
+            .and_then(|phandles| {
+                phandles
+                    .into_iter()
+                    .map(|phandle| self.resolve(phandle))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .map_err(|err| err.in_property(regions.name()))?
+            .into_iter()
+            .map(|node| {
+                let path = node.path();
+                ReservedRegion::from_node(node).map_err(|err| err.in_node(&path))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::byte_utils::{read_aligned_be_u32, read_aligned_sized_strings, BLOCK_SIZE};
+use crate::error::{ErrorKind, Result};
+use crate::prop::{NodeProperty, PropertyValue};
+use crate::walker::InheritedValues;
+
+/// Decodes the raw value of a property, strings may borrow from the raw value
+pub type Decoder = for<'v> fn(&'v [u8], &PropertyContext<'_>) -> Result<PropertyValue<'v>>;
+
+/// What a [Decoder] knows about the property it decodes
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyContext<'c> {
+    name: &'c str,
+    inherited: InheritedValues,
+    owned: InheritedValues,
+}
+
+impl<'c> PropertyContext<'c> {
+    pub(crate) fn new(name: &'c str, inherited: InheritedValues, owned: InheritedValues) -> Self {
+        Self {
+            name,
+            inherited,
+            owned,
+        }
+    }
+
+    /// Get the name of the property
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Get the `#address-cells` of the bus its node sits on
+    pub fn address_cells(&self) -> Option<u32> {
+        self.inherited.address_cells
+    }
+
+    /// Get the `#size-cells` of the bus its node sits on
+    pub fn size_cells(&self) -> Option<u32> {
+        self.inherited.size_cells
+    }
+
+    /// Get the `#address-cells` its node declares for its children
+    pub fn child_address_cells(&self) -> Option<u32> {
+        self.owned.address_cells
+    }
+
+    /// Get the `#size-cells` its node declares for its children
+    pub fn child_size_cells(&self) -> Option<u32> {
+        self.owned.size_cells
+    }
+}
+
+/// Chooses how the properties of a tree are decoded
+/// The decoder is picked once, when the tree is parsed
+pub trait PropertyRegistry {
+    /// Get the decoder of the property `name` of a node with the `compatible` strings,
+    /// `None` leaves it to the built-in rules
+    fn decoder(&self, compatible: &[&str], name: &str) -> Option<Decoder>;
+}
+
+/// The built-in rules, properties named by the specification have their types
+/// and the others are guessed from their value
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRegistry;
+
+impl PropertyRegistry for DefaultRegistry {
+    fn decoder(&self, _compatible: &[&str], _name: &str) -> Option<Decoder> {
+        None
+    }
+}
+
+/// Decoders registered by property name, or by a compatible string and property name,
+/// on top of the built-in rules
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    names: BTreeMap<String, Decoder>,
+    // compatible string, then property name, so both are looked up by `&str`
+    bindings: BTreeMap<String, BTreeMap<String, Decoder>>,
+}
+
+impl Registry {
+    /// Creates a registry which decodes everything with the built-in rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the property `name` of every node with `decoder`
+    pub fn with_property(mut self, name: &str, decoder: Decoder) -> Self {
+        self.names.insert(name.into(), decoder);
+        self
+    }
+
+    /// Decode the property `name` of the nodes compatible with `compatible` with `decoder`,
+    /// which takes precedence over the one registered for the name alone
+    pub fn with_binding(mut self, compatible: &str, name: &str, decoder: Decoder) -> Self {
+        self.bindings
+            .entry(compatible.into())
+            .or_default()
+            .insert(name.into(), decoder);
+        self
+    }
+}
+
+impl PropertyRegistry for Registry {
+    fn decoder(&self, compatible: &[&str], name: &str) -> Option<Decoder> {
+        // compatible strings go from the most specific to the most general
+        compatible
+            .iter()
+            .find_map(|compatible| self.bindings.get(*compatible)?.get(name).copied())
+            .or_else(|| self.names.get(name).copied())
+    }
+}
+
+/// Decode with the built-in rules, for decoders which only handle some values themselves
+pub fn builtin<'v>(raw: &'v [u8], context: &PropertyContext<'_>) -> Result<PropertyValue<'v>> {
+    NodeProperty::parse_value(raw, context.name, context.inherited, context.owned)
+}
+
+/// Decode as a list of u32 cells, even a single one
+pub fn integers<'v>(raw: &'v [u8], _context: &PropertyContext<'_>) -> Result<PropertyValue<'v>> {
+    if raw.is_empty() || raw.len() % BLOCK_SIZE != 0 {
+        return Err(ErrorKind::ParsingFailed.into());
+    }
+    let cells = (0..raw.len() / BLOCK_SIZE)
+        .map(|index| read_aligned_be_u32(raw, index).map(u64::from))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ErrorKind::ParsingFailed)?;
+    Ok(PropertyValue::Integers(cells))
+}
+
+/// Decode as a list of strings, even a single one
+pub fn strings<'v>(raw: &'v [u8], _context: &PropertyContext<'_>) -> Result<PropertyValue<'v>> {
+    read_aligned_sized_strings(raw, 0, raw.len())
+        .filter(|strings| !strings.is_empty())
+        .map(PropertyValue::Strings)
+        .ok_or(ErrorKind::ParsingFailed.into())
+}
+
+/// Decode as a single phandle
+pub fn phandle<'v>(raw: &'v [u8], _context: &PropertyContext<'_>) -> Result<PropertyValue<'v>> {
+    match raw.len() {
+        4 => Ok(PropertyValue::PHandle(
+            read_aligned_be_u32(raw, 0).ok_or(ErrorKind::ParsingFailed)?,
+        )),
+        _ => Err(ErrorKind::ParsingFailed.into()),
+    }
+}
@@ -2,37 +2,37 @@ use crate::node::DeviceTreeNode;
 use crate::prop::{NodeProperty, PropertyValue};
 
 /// Indicates a struct should have [NodeProperty] and can be indexed by its name
-pub trait HasNamedProperty {
+pub trait HasNamedProperty<'a> {
     /// Having properties or not
     fn has_props(&self) -> bool;
 
     /// Look for a property by its name
-    fn find_prop(&self, name: &str) -> Option<&NodeProperty>;
+    fn find_prop(&self, name: &str) -> Option<&NodeProperty<'a>>;
 
-    /// Look for a property by its name and returning its value if present
-    fn of_value(&self, name: &str) -> Option<&PropertyValue>;
+    /// Look for a property by its name and returning its decoded value if present
+    fn of_value(&self, name: &str) -> Option<PropertyValue<'_>>;
 }
 
 /// Indicates a struct should have [DeviceTreeNode] and can be indexed by its name
-pub trait HasNamedChildNode {
+pub trait HasNamedChildNode<'a> {
     /// Having child nodes or not
     fn has_children(&self) -> bool;
 
     /// Look for a child by its name
-    fn find_child(&self, name: &str) -> Option<&DeviceTreeNode>;
+    fn find_child(&self, name: &str) -> Option<&DeviceTreeNode<'a>>;
 }
 
 /// Ability to access a node's property value
 pub trait FindPropertyValue {
-    /// Find property and get its value in one call
-    fn value(&self, prop_name: &str) -> Option<&PropertyValue>;
+    /// Find property and decode its value in one call
+    fn value(&self, prop_name: &str) -> Option<PropertyValue<'_>>;
 }
 
-impl<T> FindPropertyValue for T
+impl<'a, T> FindPropertyValue for T
 where
-    T: HasNamedProperty,
+    T: HasNamedProperty<'a>,
 {
-    fn value(&self, prop_name: &str) -> Option<&PropertyValue> {
-        self.find_prop(prop_name).map(|f| f.value())
+    fn value(&self, prop_name: &str) -> Option<PropertyValue<'_>> {
+        self.of_value(prop_name)
     }
 }
@@ -0,0 +1,164 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::device_tree::{DeviceTree, NodeRef};
+use crate::node::DeviceTreeNode;
+use crate::traits::HasNamedProperty;
+
+// defaults of the spec when a bus does not declare its cells
+const DEFAULT_ADDRESS_CELLS: u32 = 2;
+const DEFAULT_SIZE_CELLS: u32 = 1;
+
+impl<'a> DeviceTree<'a> {
+    /// Translate an address on the bus `node` sits on into the CPU physical address space
+    /// Follows the `ranges` of every bus up to the root, an empty `ranges` maps one to one
+    /// Returns `None` if a bus has no `ranges` or none of its ranges covers the address
+    pub fn translate_address(&self, node: &DeviceTreeNode<'a>, address: u128) -> Option<u64> {
+        self.node_ref(node)?.translate_address(address)
+    }
+
+    /// Get the `(address, size)` pairs of the `reg` of `node`, translated into CPU physical addresses
+    /// Returns `None` if it has no valid `reg` or one of its addresses cannot be translated
+    pub fn cpu_regs(&self, node: &DeviceTreeNode<'a>) -> Option<Vec<(u64, u64)>> {
+        self.node_ref(node)?.cpu_regs()
+    }
+}
+
+impl NodeRef<'_, '_> {
+    // `DeviceTree::translate_address` for a node whose way up is already known
+    pub(crate) fn translate_address(&self, address: u128) -> Option<u64> {
+        let address = match self.parent() {
+            Some(bus) => translate_up(bus, address, "ranges", true)?,
+            None => address,
+        };
+        u64::try_from(address).ok()
+    }
+
+    // `DeviceTree::cpu_regs` for a node whose way up is already known
+    pub(crate) fn cpu_regs(&self) -> Option<Vec<(u64, u64)>> {
+        let bus = self.parent()?;
+        let address_cells = cells_of(&bus, "#address-cells", DEFAULT_ADDRESS_CELLS);
+        let size_cells = cells_of(&bus, "#size-cells", DEFAULT_SIZE_CELLS);
+        let reg = self.find_prop("reg")?.cells().ok()?;
+        let entry = address_cells + size_cells;
+        if entry == 0 || reg.len() % entry != 0 {
+            return None;
+        }
+        reg.chunks_exact(entry)
+            .map(|entry| {
+                let (address, size) = entry.split_at(address_cells);
+                let address = self.translate_address(join_cells(address)?)?;
+                Some((address, u64::try_from(join_cells(size)?).ok()?))
+            })
+            .collect()
+    }
+}
+
+// carries `address` from the child side of `bus` up to the root through the `name` ranges of
+// every bus on the way, an empty property maps one to one and so does a missing one unless `required`
+pub(crate) fn translate_up(
+    bus: NodeRef,
+    address: u128,
+    name: &str,
+    required: bool,
+) -> Option<u128> {
+    let mut bus = bus;
+    let mut address = address;
+    while let Some(parent) = bus.parent() {
+        match bus.find_prop(name) {
+            Some(ranges) if !ranges.raw().is_empty() => {
+                address = map_range(&bus, &parent, name, address, true)?;
+            }
+            None if required => return None,
+            _ => {}
+        }
+        bus = parent;
+    }
+    Some(address)
+}
+
+// maps `address` between the child side of the `name` ranges of `bus` and the side of its `parent`,
+// upwards from the child side if `up`, otherwise downwards
+pub(crate) fn map_range(
+    bus: &NodeRef,
+    parent: &NodeRef,
+    name: &str,
+    address: u128,
+    up: bool,
+) -> Option<u128> {
+    // PCI addresses carry flags in their high cell, only the address space of `ranges` has to match
+    let check_space = name == "ranges" && is_pci(bus);
+    for (child, parent, size) in range_rows(bus, parent, name)? {
+        if up {
+            const SPACE_CODE: u128 = 0x0300_0000 << 64;
+            if check_space && (child ^ address) & SPACE_CODE != 0 {
+                continue;
+            }
+            let base = strip_flags(bus, child);
+            let local = strip_flags(bus, address);
+            if local >= base && local - base < size {
+                return parent.checked_add(local - base);
+            }
+        } else if address >= parent && address - parent < size {
+            return strip_flags(bus, child).checked_add(address - parent);
+        }
+    }
+    None
+}
+
+// `(child address, parent address, size)` rows of the `name` ranges of `bus`
+pub(crate) fn range_rows(
+    bus: &NodeRef,
+    parent: &NodeRef,
+    name: &str,
+) -> Option<Vec<(u128, u128, u128)>> {
+    let ranges = bus.find_prop(name)?.cells().ok()?;
+    let child_cells = cells_of(bus, "#address-cells", DEFAULT_ADDRESS_CELLS);
+    let parent_cells = cells_of(parent, "#address-cells", DEFAULT_ADDRESS_CELLS);
+    let size_cells = cells_of(bus, "#size-cells", DEFAULT_SIZE_CELLS);
+    let row = child_cells + parent_cells + size_cells;
+    if row == 0 || ranges.len() % row != 0 {
+        return None;
+    }
+    ranges
+        .chunks_exact(row)
+        .map(|range| {
+            let (child, rest) = range.split_at(child_cells);
+            let (parent, size) = rest.split_at(parent_cells);
+            Some((join_cells(child)?, join_cells(parent)?, join_cells(size)?))
+        })
+        .collect()
+}
+
+// drops the flags cell of a PCI address, leaving the 64 bits address
+pub(crate) fn strip_flags(bus: &NodeRef, address: u128) -> u128 {
+    if is_pci(bus) {
+        address as u64 as u128
+    } else {
+        address
+    }
+}
+
+fn is_pci(bus: &NodeRef) -> bool {
+    cells_of(bus, "#address-cells", DEFAULT_ADDRESS_CELLS) == 3
+        && bus
+            .find_prop("device_type")
+            .is_some_and(|prop| prop.raw() == b"pci\0")
+}
+
+fn cells_of(node: &NodeRef, name: &str, default: u32) -> usize {
+    node.prop_u32(name).unwrap_or(default) as usize
+}
+
+fn join_cells(cells: &[u32]) -> Option<u128> {
+    if cells.len() > 4 {
+        return None;
+    }
+    Some(
+        cells
+            .iter()
+            .fold(0u128, |number, cell| (number << 32) | *cell as u128),
+    )
+}
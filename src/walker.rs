@@ -0,0 +1,517 @@
+use crate::byte_utils::{
+    align_size, locate_block, read_aligned_be_number, read_aligned_be_u32, read_aligned_name,
+    read_name, BLOCK_SIZE,
+};
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::header::{DeviceTreeHeader, FDT_MAGIC};
+use crate::reservation::MemoryReservations;
+
+pub(crate) const FDT_BEGIN_NODE: u32 = 0x1;
+pub(crate) const FDT_END_NODE: u32 = 0x2;
+pub(crate) const FDT_PROP: u32 = 0x3;
+pub(crate) const FDT_NOP: u32 = 0x4;
+pub(crate) const FDT_END: u32 = 0x9;
+
+/// `#address-cells` and `#size-cells` declared by a node, handed down to its children
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct InheritedValues {
+    pub address_cells: Option<u32>,
+    pub size_cells: Option<u32>,
+}
+
+/// A flattened device tree which is walked in place
+/// Nothing is copied or allocated, every node and property is read straight out of the blob
+#[derive(Clone, Copy)]
+pub struct Fdt<'a> {
+    // the blob cut to its total size
+    data: &'a [u8],
+    structs: &'a [u8],
+    strings: &'a [u8],
+    pub(crate) header: DeviceTreeHeader,
+}
+
+/// How thoroughly the header of a blob is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// Only what walking the blob relies on: a known layout and blocks within the total size
+    Lenient,
+    /// Also consistent versions, an aligned reservation block and blocks which do not overlap
+    Strict,
+}
+
+impl<'a> Fdt<'a> {
+    /// Checks the header of a slice of bytes and constructs [Fdt]
+    /// The blocks the header points at must lie within its total size, which must fit in `data`
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self> {
+        Self::from_bytes_with(data, Validation::Lenient)
+    }
+
+    /// Checks the header of a slice of bytes as thoroughly as `validation` asks and constructs [Fdt]
+    pub fn from_bytes_with(data: &'a [u8], validation: Validation) -> Result<Self> {
+        match read_aligned_be_u32(data, 0) {
+            Some(FDT_MAGIC) => {}
+            Some(_) => return Err(DeviceTreeError::from(ErrorKind::InvalidMagicNumber).at(0)),
+            None => return Err(ErrorKind::NotEnoughLength.into()),
+        }
+        let header = DeviceTreeHeader::from_bytes(data)?;
+        header.validate(validation)?;
+        let data = data
+            .get(..header.total_size as usize)
+            .ok_or(ErrorKind::NotEnoughLength)?;
+        let (struct_start, struct_size) = header.struct_block();
+        let (strings_start, strings_size) = header.strings_block();
+        Ok(Self {
+            data,
+            structs: block(data, struct_start, struct_size),
+            strings: block(data, strings_start, strings_size),
+            header,
+        })
+    }
+
+    /// Check the header against the invariants of `validation`
+    pub fn validate(&self, validation: Validation) -> Result<()> {
+        self.header.validate(validation)
+    }
+
+    /// Its total size extracted from the header
+    pub fn total_size(&self) -> usize {
+        self.header.total_size as usize
+    }
+
+    /// Its version extracted from the header
+    pub fn version(&self) -> usize {
+        self.header.version as usize
+    }
+
+    /// Its boot cpu id extracted from the header
+    pub fn boot_cpu_id(&self) -> usize {
+        self.header.boot_cpu_id as usize
+    }
+
+    /// Iterate over the entries of the memory reservation block
+    pub fn memory_reservations(&self) -> MemoryReservations<'a> {
+        MemoryReservations::new(
+            self.data,
+            self.header.off_mem_reserved as usize,
+            self.header.total_size as usize,
+        )
+    }
+
+    /// Walk all the tokens of the structure block from the beginning
+    pub fn tokens(&self) -> Tokens<'a> {
+        Tokens {
+            structs: self.structs,
+            strings: self.strings,
+            base: self.header.off_dt_struct as usize,
+            version: self.header.version,
+            block: 0,
+            finished: false,
+        }
+    }
+
+    /// Get the root node, which must be the first token of the structure block
+    pub fn root(&self) -> Result<FdtNode<'a>> {
+        let mut tokens = self.tokens();
+        match tokens.next() {
+            Some(Ok(Token::BeginNode(name))) => Ok(FdtNode {
+                name,
+                body: tokens,
+                inherited: InheritedValues::default(),
+            }),
+            Some(Err(err)) => Err(err),
+            _ => Err(ErrorKind::InvalidToken.into()),
+        }
+    }
+
+    /// Find the node by given node path
+    pub fn find_node(&self, path: &str) -> Option<FdtNode<'a>> {
+        let mut slices = path.split('/');
+        if let Some("") = slices.next() {
+            let mut first = self.root().ok()?;
+            for i in slices.filter(|s| !s.is_empty()) {
+                first = first.find_child(i)?;
+            }
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+// a block of `data` the header points at, whose bounds the header validation checked
+fn block(data: &[u8], offset: u32, size: u32) -> &[u8] {
+    let offset = offset as usize;
+    &data[offset..offset + size as usize]
+}
+
+/// A token of the structure block
+#[derive(Debug, Clone, Copy)]
+pub enum Token<'a> {
+    /// `FDT_BEGIN_NODE` followed by the node's name
+    BeginNode(&'a str),
+    /// `FDT_PROP` with its name resolved from the strings block
+    Property(FdtProperty<'a>),
+    /// `FDT_END_NODE`
+    EndNode,
+    /// `FDT_END`
+    End,
+}
+
+/// Cursor over the tokens of the structure block, `FDT_NOP` is skipped
+/// It stops after `FDT_END` or the first error, nothing is read past the structure block
+#[derive(Clone, Copy)]
+pub struct Tokens<'a> {
+    structs: &'a [u8],
+    strings: &'a [u8],
+    // offset of the structure block in the blob
+    base: usize,
+    version: u32,
+    block: usize,
+    finished: bool,
+}
+
+impl<'a> Tokens<'a> {
+    fn read_token(&mut self) -> Result<Token<'a>> {
+        loop {
+            let token =
+                read_aligned_be_u32(self.structs, self.block).ok_or(ErrorKind::NotEnoughLength)?;
+            match token {
+                FDT_BEGIN_NODE => {
+                    let name = read_aligned_name(self.structs, self.block + 1)
+                        .ok_or(ErrorKind::ParsingFailed)?;
+                    // the terminating zero always takes place so an empty name occupies one block
+                    self.block += 1 + align_size(name.len() + 1);
+                    // before version 16 nodes are named by their full path
+                    if self.version < 16 {
+                        return Ok(Token::BeginNode(name.rsplit('/').next().unwrap_or(name)));
+                    }
+                    return Ok(Token::BeginNode(name));
+                }
+                FDT_PROP => {
+                    let size = read_aligned_be_u32(self.structs, self.block + 1)
+                        .ok_or(ErrorKind::NotEnoughLength)? as usize;
+                    let name_offset = read_aligned_be_u32(self.structs, self.block + 2)
+                        .ok_or(ErrorKind::NotEnoughLength)?
+                        as usize;
+                    let name =
+                        read_name(self.strings, name_offset).ok_or(ErrorKind::ParsingFailed)?;
+                    let mut first = locate_block(self.block + 3);
+                    // before version 16 values of 8 bytes or more are aligned on 8 bytes
+                    if self.version < 16 && size >= 8 && first % 8 != 0 {
+                        first += BLOCK_SIZE;
+                        self.block += 1;
+                    }
+                    let value = first
+                        .checked_add(size)
+                        .and_then(|end| self.structs.get(first..end))
+                        .ok_or(ErrorKind::NotEnoughLength)?;
+                    self.block += 3 + align_size(size);
+                    return Ok(Token::Property(FdtProperty::new(name, value)));
+                }
+                FDT_END_NODE => {
+                    self.block += 1;
+                    return Ok(Token::EndNode);
+                }
+                FDT_NOP => self.block += 1,
+                FDT_END => {
+                    self.block += 1;
+                    return Ok(Token::End);
+                }
+                _ => return Err(ErrorKind::InvalidToken.into()),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let offset = self.base + locate_block(self.block);
+        let res = self.read_token().map_err(|err| err.at(offset));
+        if matches!(res, Ok(Token::End) | Err(_)) {
+            self.finished = true;
+        }
+        Some(res)
+    }
+}
+
+/// A node read in place from the structure block
+#[derive(Clone, Copy)]
+pub struct FdtNode<'a> {
+    name: &'a str,
+    // positioned right after the node's `FDT_BEGIN_NODE`
+    body: Tokens<'a>,
+    inherited: InheritedValues,
+}
+
+impl<'a> FdtNode<'a> {
+    /// Get the name of this node
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Iterate over its properties
+    pub fn props(&self) -> FdtProperties<'a> {
+        FdtProperties {
+            tokens: self.body,
+            finished: false,
+        }
+    }
+
+    /// Iterate over its direct children
+    pub fn children(&self) -> FdtChildren<'a> {
+        FdtChildren {
+            tokens: self.body,
+            owned: self.owned(),
+            finished: false,
+        }
+    }
+
+    /// Look for a property by its name
+    pub fn find_prop(&self, name: &str) -> Option<FdtProperty<'a>> {
+        self.props().flatten().find(|p| p.name() == name)
+    }
+
+    /// Look for a child by its name
+    pub fn find_child(&self, name: &str) -> Option<FdtNode<'a>> {
+        self.children().flatten().find(|n| n.name() == name)
+    }
+
+    /// `#address-cells` declared by this node for its children
+    pub fn address_cells(&self) -> Option<u32> {
+        self.find_prop("#address-cells")?.as_u32()
+    }
+
+    /// `#size-cells` declared by this node for its children
+    pub fn size_cells(&self) -> Option<u32> {
+        self.find_prop("#size-cells")?.as_u32()
+    }
+
+    /// Decode its `reg` property with the cells declared by its parent
+    pub fn reg(&self) -> Option<FdtReg<'a>> {
+        Some(FdtReg {
+            value: self.find_prop("reg")?.raw(),
+            address_cells: self.inherited.address_cells? as usize,
+            size_cells: self.inherited.size_cells? as usize,
+            index: 0,
+        })
+    }
+
+    pub(crate) fn owned(&self) -> InheritedValues {
+        InheritedValues {
+            address_cells: self.address_cells(),
+            size_cells: self.size_cells(),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn inherited(&self) -> InheritedValues {
+        self.inherited
+    }
+}
+
+/// Iterator for the properties of a [FdtNode]
+pub struct FdtProperties<'a> {
+    tokens: Tokens<'a>,
+    finished: bool,
+}
+
+impl<'a> Iterator for FdtProperties<'a> {
+    type Item = Result<FdtProperty<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.tokens.next()? {
+            Ok(Token::Property(prop)) => Some(Ok(prop)),
+            Ok(_) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Iterator for the direct children of a [FdtNode]
+pub struct FdtChildren<'a> {
+    tokens: Tokens<'a>,
+    owned: InheritedValues,
+    finished: bool,
+}
+
+impl<'a> FdtChildren<'a> {
+    fn read_child(&mut self) -> Option<Result<FdtNode<'a>>> {
+        loop {
+            match self.tokens.next()? {
+                Ok(Token::Property(_)) => continue,
+                Ok(Token::BeginNode(name)) => {
+                    let child = FdtNode {
+                        name,
+                        body: self.tokens,
+                        inherited: self.owned,
+                    };
+                    // skip the whole subtree of the child
+                    let mut depth = 1usize;
+                    while depth > 0 {
+                        match self.tokens.next()? {
+                            Ok(Token::BeginNode(_)) => depth += 1,
+                            Ok(Token::EndNode) => depth -= 1,
+                            Ok(Token::Property(_)) => {}
+                            Ok(Token::End) => return Some(Err(ErrorKind::InvalidToken.into())),
+                            Err(err) => return Some(Err(err)),
+                        }
+                    }
+                    return Some(Ok(child));
+                }
+                Ok(Token::EndNode) => return None,
+                Ok(Token::End) => return Some(Err(ErrorKind::InvalidToken.into())),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for FdtChildren<'a> {
+    type Item = Result<FdtNode<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let res = self.read_child();
+        if !matches!(res, Some(Ok(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
+
+/// A property read in place from the structure block
+#[derive(Debug, Clone, Copy)]
+pub struct FdtProperty<'a> {
+    name: &'a str,
+    value: &'a [u8],
+}
+
+impl<'a> FdtProperty<'a> {
+    pub(crate) fn new(name: &'a str, value: &'a [u8]) -> Self {
+        Self { name, value }
+    }
+
+    /// Get its name
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get its value as it is stored in the blob
+    pub fn raw(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Read its value as a single big-endian u32
+    pub fn as_u32(&self) -> Option<u32> {
+        if self.value.len() == 4 {
+            read_aligned_be_u32(self.value, 0)
+        } else {
+            None
+        }
+    }
+
+    /// Read its value as a single big-endian u64, a single u32 is also accepted
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value.len() {
+            4 => read_aligned_be_number(self.value, 0, 1),
+            8 => read_aligned_be_number(self.value, 0, 2),
+            _ => None,
+        }
+    }
+
+    /// Read its value as a single zero-terminated string
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.value.split_last() {
+            Some((0, s)) if !s.contains(&0) => core::str::from_utf8(s).ok(),
+            _ => None,
+        }
+    }
+
+    /// Read its value as a list of zero-terminated strings
+    pub fn as_str_list(&self) -> StrList<'a> {
+        StrList { value: self.value }
+    }
+
+    /// Read its value as a list of big-endian u32 cells, `None` if its length is not a multiple of 4
+    pub fn as_cells(&self) -> Option<Cells<'a>> {
+        (self.value.len() % 4 == 0).then_some(Cells {
+            value: self.value,
+            index: 0,
+        })
+    }
+}
+
+/// Iterator for a list of zero-terminated strings, stops at the first malformed one
+pub struct StrList<'a> {
+    value: &'a [u8],
+}
+
+impl<'a> Iterator for StrList<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = self.value.iter().position(|b| *b == 0)?;
+        let res = core::str::from_utf8(&self.value[..end]).ok();
+        self.value = if res.is_some() {
+            &self.value[end + 1..]
+        } else {
+            &[]
+        };
+        res
+    }
+}
+
+/// Iterator for a list of big-endian u32 cells
+pub struct Cells<'a> {
+    value: &'a [u8],
+    index: usize,
+}
+
+impl Iterator for Cells<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = read_aligned_be_u32(self.value, self.index)?;
+        self.index += 1;
+        Some(res)
+    }
+}
+
+/// Iterator for the `(address, size)` pairs of a `reg` property
+pub struct FdtReg<'a> {
+    value: &'a [u8],
+    address_cells: usize,
+    size_cells: usize,
+    index: usize,
+}
+
+impl Iterator for FdtReg<'_> {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.address_cells + self.size_cells == 0 {
+            return None;
+        }
+        let address = read_aligned_be_number(self.value, self.index, self.address_cells)?;
+        let size =
+            read_aligned_be_number(self.value, self.index + self.address_cells, self.size_cells)?;
+        self.index += self.address_cells + self.size_cells;
+        Some((address, size))
+    }
+}
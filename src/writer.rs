@@ -0,0 +1,142 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+use crate::byte_utils::BLOCK_SIZE;
+use crate::device_tree::DeviceTree;
+use crate::error::{DeviceTreeError, ErrorKind, Result};
+use crate::header::{DeviceTreeHeader, FDT_MAGIC, HEADER_SIZE};
+use crate::node::DeviceTreeNode;
+use crate::walker::{FDT_BEGIN_NODE, FDT_END, FDT_END_NODE, FDT_PROP};
+
+const VERSION: u32 = 17;
+const LAST_COMP_VERSION: u32 = 16;
+
+impl DeviceTree<'_> {
+    /// Serializes the tree to a version 17 blob which [DeviceTree::from_bytes] reads back
+    ///
+    /// The blocks are laid out like dtc does: header, memory reservation block,
+    /// structure block and strings block, where names shared by properties are stored once
+    ///
+    /// Fails with [ErrorKind::EncodingFailed] if a node name holds `\0` or `/`,
+    /// or a property name holds `\0`, as the blob would read back as another tree
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut structure = Vec::<u8>::new();
+        let mut strings = Vec::<u8>::new();
+        write_node(
+            &mut structure,
+            &mut strings,
+            self.root(),
+            &mut String::new(),
+            0,
+        )?;
+        push_u32(&mut structure, FDT_END);
+
+        let off_mem_reserved = HEADER_SIZE.next_multiple_of(8);
+        let off_dt_struct = off_mem_reserved + (self.memory_reservations().count() + 1) * 16;
+        let off_dt_strings = off_dt_struct + structure.len();
+        let total_size = off_dt_strings + strings.len();
+
+        let header = DeviceTreeHeader {
+            magic: FDT_MAGIC,
+            total_size: total_size as u32,
+            off_dt_struct: off_dt_struct as u32,
+            off_dt_strings: off_dt_strings as u32,
+            off_mem_reserved: off_mem_reserved as u32,
+            version: VERSION,
+            last_comp_version: LAST_COMP_VERSION,
+            boot_cpu_id: self.boot_cpu_id() as u32,
+            size_dt_strings: strings.len() as u32,
+            size_dt_struct: structure.len() as u32,
+        };
+
+        let mut blob = Vec::<u8>::with_capacity(total_size);
+        blob.extend_from_slice(&header.to_bytes());
+        blob.resize(off_mem_reserved, 0);
+        for reservation in self.memory_reservations() {
+            blob.extend_from_slice(&reservation.address().to_be_bytes());
+            blob.extend_from_slice(&reservation.size().to_be_bytes());
+        }
+        blob.extend_from_slice(&[0u8; 16]);
+        blob.extend_from_slice(&structure);
+        blob.extend_from_slice(&strings);
+        Ok(blob)
+    }
+}
+
+// `path` is the one of the parent, errors are given the path of the node they happen in
+fn write_node(
+    structure: &mut Vec<u8>,
+    strings: &mut Vec<u8>,
+    node: &DeviceTreeNode,
+    path: &mut String,
+    depth: usize,
+) -> Result<()> {
+    let parent_len = path.len();
+    if depth > 0 {
+        path.push('/');
+        path.push_str(node.name());
+    }
+    let res = write_node_at(structure, strings, node, path, depth)
+        .map_err(|err| err.in_node(if path.is_empty() { "/" } else { path }));
+    path.truncate(parent_len);
+    res
+}
+
+fn write_node_at(
+    structure: &mut Vec<u8>,
+    strings: &mut Vec<u8>,
+    node: &DeviceTreeNode,
+    path: &mut String,
+    depth: usize,
+) -> Result<()> {
+    if node.name().contains(['\0', '/']) {
+        return Err(ErrorKind::EncodingFailed.into());
+    }
+    push_u32(structure, FDT_BEGIN_NODE);
+    structure.extend_from_slice(node.name().as_bytes());
+    structure.push(0);
+    pad_block(structure);
+    for prop in node.props() {
+        if prop.name().contains('\0') {
+            return Err(DeviceTreeError::from(ErrorKind::EncodingFailed).in_property(prop.name()));
+        }
+        let value = prop.raw();
+        push_u32(structure, FDT_PROP);
+        push_u32(structure, value.len() as u32);
+        push_u32(structure, string_offset(strings, prop.name()));
+        structure.extend_from_slice(value);
+        pad_block(structure);
+    }
+    for child in node.nodes() {
+        write_node(structure, strings, child, path, depth + 1)?;
+    }
+    push_u32(structure, FDT_END_NODE);
+    Ok(())
+}
+
+// reuses any zero-terminated occurrence, including the tail of a longer name
+fn string_offset(strings: &mut Vec<u8>, name: &str) -> u32 {
+    let name = name.as_bytes();
+    let found = strings
+        .windows(name.len() + 1)
+        .position(|w| w[name.len()] == 0 && &w[..name.len()] == name);
+    match found {
+        Some(offset) => offset as u32,
+        None => {
+            let offset = strings.len();
+            strings.extend_from_slice(name);
+            strings.push(0);
+            offset as u32
+        }
+    }
+}
+
+fn push_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+fn pad_block(buffer: &mut Vec<u8>) {
+    buffer.resize(buffer.len().next_multiple_of(BLOCK_SIZE), 0);
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::clock::ClockKind;
+use dtb_parser::device_tree::DeviceTree;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    osc: oscillator {
+        compatible = "fixed-clock";
+        #clock-cells = <0>;
+        clock-frequency = <24000000>;
+        clock-accuracy = <100>;
+        clock-output-names = "osc24m";
+    };
+
+    half: divider {
+        compatible = "fixed-factor-clock";
+        #clock-cells = <0>;
+        clocks = <&osc>;
+        clock-mult = <1>;
+        clock-div = <2>;
+    };
+
+    ccu: clock-controller@1000 {
+        #clock-cells = <1>;
+        clocks = <&osc>;
+        clock-output-names = "pll", "ahb", "apb";
+    };
+
+    uart {
+        clocks = <&ccu 2>, <&half>;
+        clock-names = "bus", "baud";
+        assigned-clocks = <&ccu 0>, <&ccu 1>;
+        assigned-clock-rates = <600000000>, <0>;
+        assigned-clock-parents = <0>, <&osc>;
+    };
+};
+"#;
+
+#[test]
+fn providers() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let providers = tree.clock_providers();
+    assert_eq!(providers.len(), 3);
+
+    assert_eq!(
+        providers[0].kind(),
+        ClockKind::Fixed {
+            frequency: 24000000,
+            accuracy: Some(100)
+        }
+    );
+    assert_eq!(providers[0].output_names(), ["osc24m"]);
+    assert_eq!(
+        providers[1].kind(),
+        ClockKind::FixedFactor { mult: 1, div: 2 }
+    );
+    assert_eq!(providers[1].rate().unwrap(), Some(12000000));
+    assert_eq!(providers[2].kind(), ClockKind::Other);
+    assert_eq!(providers[2].cells(), 1);
+    assert_eq!(providers[2].rate().unwrap(), None);
+}
+
+#[test]
+fn consumers() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let uart = tree.node_ref(tree.find_node("/uart").unwrap()).unwrap();
+
+    let clocks = uart.clocks().unwrap();
+    assert_eq!(clocks.len(), 2);
+    assert_eq!(clocks[0].provider().node().path(), "/clock-controller@1000");
+    assert_eq!(clocks[0].index(), 2);
+    assert_eq!(clocks[0].output_name(), Some("apb"));
+
+    let baud = uart.clock("baud").unwrap().unwrap();
+    assert_eq!(baud.provider().node().name(), "divider");
+    assert_eq!(baud.index(), 0);
+    assert_eq!(baud.output_name(), None);
+    assert_eq!(baud.provider().rate().unwrap(), Some(12000000));
+    assert!(uart.clock("apb").unwrap().is_none());
+
+    let assigned = uart.assigned_clocks().unwrap();
+    assert_eq!(assigned.len(), 2);
+    assert_eq!(assigned[0].clock().output_name(), Some("pll"));
+    assert_eq!(assigned[0].rate(), Some(600000000));
+    assert!(assigned[0].parent().is_none());
+    assert_eq!(assigned[1].rate(), None);
+    assert_eq!(assigned[1].parent().unwrap().output_name(), Some("osc24m"));
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::dma::DmaWindow;
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+const DMA: &str = r#"
+    /dts-v1/;
+    / {
+        #address-cells = <2>;
+        #size-cells = <2>;
+        dma-noncoherent;
+
+        soc {
+            #address-cells = <1>;
+            #size-cells = <1>;
+            ranges;
+            dma-ranges = <0x0 0x0 0x80000000 0x40000000>;
+            dma-coherent;
+
+            bus {
+                #address-cells = <1>;
+                #size-cells = <1>;
+                ranges;
+                dma-ranges;
+
+                ethernet {
+                };
+            };
+
+            usb {
+                dma-noncoherent;
+            };
+        };
+
+        uart {
+        };
+    };
+"#;
+
+#[test]
+fn dma_translation() {
+    let tree = DeviceTree::from_dts(DMA).unwrap();
+    let ethernet = tree.find_node("/soc/bus/ethernet").unwrap();
+    assert_eq!(tree.dma_to_cpu(ethernet, 0x1000), Some(0x8000_1000));
+    assert_eq!(tree.cpu_to_dma(ethernet, 0x8000_1000), Some(0x1000));
+    assert_eq!(tree.dma_to_cpu(ethernet, 0x4000_0000), None);
+    assert_eq!(tree.cpu_to_dma(ethernet, 0x1000), None);
+
+    // no dma-ranges on the way maps one to one
+    let uart = tree.find_node("/uart").unwrap();
+    assert_eq!(tree.dma_to_cpu(uart, 0x1000), Some(0x1000));
+    assert_eq!(tree.cpu_to_dma(uart, 0x1000), Some(0x1000));
+}
+
+#[test]
+fn dma_windows() {
+    let tree = DeviceTree::from_dts(DMA).unwrap();
+    let ethernet = tree.find_node("/soc/bus/ethernet").unwrap();
+    let windows = tree.dma_windows(ethernet).unwrap();
+    assert_eq!(
+        windows,
+        [DmaWindow {
+            dma_start: 0,
+            cpu_start: 0x8000_0000,
+            size: 0x4000_0000,
+        }]
+    );
+    assert_eq!(windows[0].dma_limit(), 0x3fff_ffff);
+    let uart = tree.find_node("/uart").unwrap();
+    assert!(tree.dma_windows(uart).unwrap().is_empty());
+}
+
+#[test]
+fn dma_coherence() {
+    let tree = DeviceTree::from_dts(DMA).unwrap();
+    assert!(tree.dma_coherent(tree.find_node("/soc/bus/ethernet").unwrap()));
+    assert!(!tree.dma_coherent(tree.find_node("/soc/usb").unwrap()));
+    assert!(!tree.dma_coherent(tree.find_node("/uart").unwrap()));
+
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    assert!(tree.dma_coherent(tree.find_node("/soc/pci@30000000").unwrap()));
+    assert!(!tree.dma_coherent(tree.find_node("/soc/uart@10000000").unwrap()));
+}
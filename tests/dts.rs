@@ -0,0 +1,335 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::error::ErrorKind;
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::traits::{FindPropertyValue, HasNamedChildNode, HasNamedProperty};
+use dtb_parser::{DeviceTree, Fdt};
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+const DTS: &str = include_str!("device.dts");
+
+#[test]
+fn same_as_dtc() {
+    let tree = DeviceTree::from_dts(DTS).unwrap();
+    assert_eq!(tree.to_bytes().unwrap(), DTB);
+    assert_eq!(tree.total_size(), DTB.len());
+}
+
+#[test]
+fn values() {
+    let tree = DeviceTree::from_dts(
+        r#"
+        /dts-v1/;
+        /memreserve/ 0x80000000 0x10000;
+        / {
+            #address-cells = <1>;
+            #size-cells = <1>;
+            /* bytes, escapes and expressions */
+            node@1000 {
+                reg = <0x1000 (0x10 * 2)>;
+                mac = [00 11 2233 44 55];
+                name-list = "a\tb", "c\x41\101";
+                wide = /bits/ 64 <0x1 0x200000000>;
+                narrow = /bits/ 8 <0xff ('A' + 1)>;
+                neg = <(-1)>;
+                flag;
+            };
+        };
+    "#,
+    )
+    .unwrap();
+    assert_eq!(tree.memory_reservations().count(), 1);
+    let node = tree.find_node("/node@1000").unwrap();
+    assert!(matches!(
+        node.value("reg"),
+        Some(PropertyValue::Address(0x1000, 0x20))
+    ));
+    assert!(matches!(
+        node.value("name-list"),
+        Some(PropertyValue::Strings(it)) if it == ["a\tb", "cAA"]
+    ));
+    assert!(matches!(
+        node.value("neg"),
+        Some(PropertyValue::Integer(0xffff_ffff))
+    ));
+    assert!(matches!(node.value("flag"), Some(PropertyValue::None)));
+
+    let blob = tree.to_bytes().unwrap();
+    let fdt = Fdt::from_bytes(&blob).unwrap();
+    let node = fdt.find_node("/node@1000").unwrap();
+    let raw = |name: &str| node.find_prop(name).unwrap().raw();
+    assert_eq!(raw("mac"), [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+    assert_eq!(
+        raw("wide"),
+        [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0]
+    );
+    assert_eq!(raw("narrow"), [0xff, 0x42]);
+}
+
+#[test]
+fn references() {
+    let tree = DeviceTree::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            intc: interrupt-controller {
+                interrupt-controller;
+                #interrupt-cells = <1>;
+            };
+            explicit: explicit {
+                phandle = <1>;
+            };
+            uart {
+                interrupt-parent = <&intc>;
+                other = <&explicit &{/interrupt-controller}>;
+                path = &intc;
+                full-path = &{/explicit};
+            };
+        };
+        &intc {
+            compatible = "riscv,plic0";
+        };
+    "#,
+    )
+    .unwrap();
+    let intc = tree.find_node("/interrupt-controller").unwrap();
+    // 1 is taken explicitly so the next free one is assigned
+    assert!(matches!(
+        intc.value("phandle"),
+        Some(PropertyValue::Integer(2))
+    ));
+    assert!(matches!(
+        intc.value("compatible"),
+        Some(PropertyValue::String("riscv,plic0"))
+    ));
+    let uart = tree.find_node("/uart").unwrap();
+    assert!(matches!(
+        uart.value("interrupt-parent"),
+        Some(PropertyValue::PHandle(2))
+    ));
+    assert!(matches!(
+        uart.value("other"),
+        Some(PropertyValue::Integers(it)) if it == [1, 2]
+    ));
+    assert!(matches!(
+        uart.value("path"),
+        Some(PropertyValue::String("/interrupt-controller"))
+    ));
+    assert!(matches!(
+        uart.value("full-path"),
+        Some(PropertyValue::String("/explicit"))
+    ));
+}
+
+#[test]
+fn deletions() {
+    let tree = DeviceTree::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            model = "board";
+            serial: uart {
+                status = "okay";
+                clock-frequency = <1024>;
+            };
+            gpio: gpio {};
+            spi {};
+        };
+        / {
+            /delete-property/ model;
+            /delete-node/ spi;
+        };
+        &serial {
+            /delete-property/ clock-frequency;
+            status = "disabled";
+        };
+        /delete-node/ &gpio;
+    "#,
+    )
+    .unwrap();
+    let root = tree.root();
+    assert!(root.find_prop("model").is_none());
+    assert!(root.find_child("spi").is_none());
+    assert!(root.find_child("gpio").is_none());
+    let uart = root.find_child("uart").unwrap();
+    assert!(uart.find_prop("clock-frequency").is_none());
+    assert!(matches!(
+        uart.value("status"),
+        Some(PropertyValue::String("disabled"))
+    ));
+}
+
+#[test]
+fn includes() {
+    let source = r#"
+        /dts-v1/;
+        /include/ "soc.dtsi"
+        &uart { status = "okay"; };
+    "#;
+    let tree = DeviceTree::from_dts_with(source, |name| match name {
+        "soc.dtsi" => Some(r#"/ { uart: uart { status = "disabled"; }; };"#.into()),
+        _ => None,
+    })
+    .unwrap();
+    assert!(matches!(
+        tree.find_node("/uart").unwrap().value("status"),
+        Some(PropertyValue::String("okay"))
+    ));
+    assert!(matches!(
+        DeviceTree::from_dts(source),
+        Err(err) if err == ErrorKind::IncludeNotFound
+    ));
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        DeviceTree::from_dts("/ { };"),
+        Err(err) if err == ErrorKind::InvalidSyntax
+    ));
+    assert!(matches!(
+        DeviceTree::from_dts("/dts-v1/; / { a = <1 2>; "),
+        Err(err) if err == ErrorKind::InvalidSyntax
+    ));
+    assert!(matches!(
+        DeviceTree::from_dts("/dts-v1/; / { a = /bits/ 8 <0x100>; };"),
+        Err(err) if err == ErrorKind::InvalidSyntax
+    ));
+    assert!(matches!(
+        DeviceTree::from_dts("/dts-v1/; / { a = <&missing>; };"),
+        Err(err) if err == ErrorKind::UnresolvedReference
+    ));
+}
+
+#[test]
+fn nesting() {
+    let nodes = |depth: usize| {
+        let mut src = String::from("/dts-v1/; / { ");
+        src.push_str(&"n { ".repeat(depth));
+        src.push_str(&"}; ".repeat(depth));
+        src.push_str("};");
+        src
+    };
+    assert!(DeviceTree::from_dts(&nodes(64)).is_ok());
+    assert!(matches!(
+        DeviceTree::from_dts(&nodes(100_000)),
+        Err(err) if err == ErrorKind::InvalidSyntax
+    ));
+    let expr = |open: &str, close: &str| {
+        let depth = 100_000;
+        format!(
+            "/dts-v1/; / {{ a = <({}1{})>; }};",
+            open.repeat(depth),
+            close.repeat(depth)
+        )
+    };
+    for unary in ["(", "-", "~", "!"] {
+        assert!(matches!(
+            DeviceTree::from_dts(&expr(unary, if unary == "(" { ")" } else { "" })),
+            Err(err) if err == ErrorKind::InvalidSyntax
+        ));
+    }
+    assert!(matches!(
+        DeviceTree::from_dts(&expr("1 ? ", " : 0")),
+        Err(err) if err == ErrorKind::InvalidSyntax
+    ));
+    assert!(DeviceTree::from_dts(&format!(
+        "/dts-v1/; / {{ a = <({}1{})>; }};",
+        "(".repeat(100),
+        ")".repeat(100)
+    ))
+    .is_ok());
+}
+
+#[test]
+fn emit_fixture() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let dts = tree.to_dts();
+    assert!(dts.starts_with("/dts-v1/;\n"));
+    assert!(dts.contains("\n/ {\n"));
+    assert!(dts.contains("\tcompatible = \"dtb_parser\";\n"));
+    assert!(dts.contains("\t\tinterrupt-map-mask = <0x1800 0x00 0x00 0x07>;\n"));
+    assert_eq!(DeviceTree::from_dts(&dts).unwrap().to_bytes().unwrap(), DTB);
+}
+
+#[test]
+fn emit_labels() {
+    let source = r#"
+        /dts-v1/;
+        /memreserve/ 0x80000000 0x10000;
+        / {
+            intc: interrupt-controller {
+                #interrupt-cells = <1>;
+                interrupt-controller;
+            };
+            uart {
+                interrupt-parent = <&intc>;
+                compatible = "ns16550a", "quoted \"name\"";
+                mac = [00 11 22];
+            };
+            __symbols__ {
+                intc = "/interrupt-controller";
+            };
+        };
+    "#;
+    let tree = DeviceTree::from_dts(source).unwrap();
+    let dts = tree.to_dts();
+    assert!(dts.contains("/memreserve/ 0x0000000080000000 0x0000000000010000;\n"));
+    assert!(dts.contains("\tintc: interrupt-controller {\n"));
+    assert!(dts.contains("\t\tinterrupt-parent = <&intc>;\n"));
+    assert!(dts.contains("\t\tcompatible = \"ns16550a\", \"quoted \\\"name\\\"\";\n"));
+    assert!(dts.contains("\t\tmac = [00 11 22];\n"));
+    assert_eq!(
+        DeviceTree::from_dts(&dts).unwrap().to_bytes().unwrap(),
+        tree.to_bytes().unwrap()
+    );
+}
+
+#[test]
+fn emit_phandle_lists() {
+    let source = r#"
+        /dts-v1/;
+        / {
+            clk: clock {
+                #clock-cells = <1>;
+            };
+            gpio: gpio {
+                gpio-controller;
+                #gpio-cells = <2>;
+
+                hog {
+                    gpio-hog;
+                    gpios = <1 0>;
+                    input;
+                };
+            };
+            pins: pins {
+            };
+            firmware: firmware {
+            };
+            device {
+                clocks = <&clk 0>, <&clk 1>;
+                reset-gpios = <&gpio 3 1>, <0>;
+                pinctrl-0 = <&pins>;
+                memory-region = <&firmware>;
+            };
+            __symbols__ {
+                clk = "/clock";
+                gpio = "/gpio";
+                pins = "/pins";
+                firmware = "/firmware";
+            };
+        };
+    "#;
+    let tree = DeviceTree::from_dts(source).unwrap();
+    let dts = tree.to_dts();
+    assert!(dts.contains("\t\tclocks = <&clk 0x00 &clk 0x01>;\n"));
+    assert!(dts.contains("\t\treset-gpios = <&gpio 0x03 0x01 0x00>;\n"));
+    assert!(dts.contains("\t\tpinctrl-0 = <&pins>;\n"));
+    assert!(dts.contains("\t\tmemory-region = <&firmware>;\n"));
+    // the specifiers of a hog have no phandle
+    assert!(dts.contains("\t\t\tgpios = <0x01 0x00>;\n"));
+    let compiled = DeviceTree::from_dts(&dts).unwrap();
+    assert_eq!(compiled.to_bytes().unwrap(), tree.to_bytes().unwrap());
+    assert_eq!(compiled.to_dts(), dts);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::node::DeviceTreeNode;
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::traits::{FindPropertyValue, HasNamedChildNode, HasNamedProperty};
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn fixup_chosen() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let chosen = tree.find_node_mut("/chosen").unwrap();
+    chosen
+        .set_prop("bootargs", PropertyValue::String("console=ttyS0"))
+        .unwrap();
+    chosen
+        .set_prop("linux,initrd-start", PropertyValue::Integer(0x8400_0000))
+        .unwrap();
+    chosen.set_prop_raw("linux,initrd-end", vec![0x84, 0x80, 0x00, 0x00]);
+    chosen.remove_prop("stdout-path").unwrap();
+
+    let blob = tree.to_bytes().unwrap();
+    let tree = DeviceTree::from_bytes(&blob).unwrap();
+    let chosen = tree.find_node("/chosen").unwrap();
+    assert!(matches!(
+        chosen.value("bootargs"),
+        Some(PropertyValue::String("console=ttyS0"))
+    ));
+    assert!(matches!(
+        chosen.value("linux,initrd-start"),
+        Some(PropertyValue::Integer(0x8400_0000))
+    ));
+    assert!(matches!(
+        chosen.value("linux,initrd-end"),
+        Some(PropertyValue::Integer(0x8480_0000))
+    ));
+    assert!(chosen.find_prop("stdout-path").is_none());
+}
+
+#[test]
+fn edit_nodes() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let root = tree.root_mut();
+    assert!(root.remove_child("memory@0").is_some());
+    assert!(root.remove_child("memory@0").is_none());
+
+    let memory = root.find_child_mut("memory@80000000").unwrap();
+    memory.rename("memory@90000000");
+    memory
+        .set_prop("reg", PropertyValue::Address(0x9000_0000, 0x400_0000))
+        .unwrap();
+
+    let soc = root.find_child_mut("soc").unwrap();
+    let timer = soc.add_child(DeviceTreeNode::new("timer@2000000"));
+    timer
+        .set_prop(
+            "compatible",
+            PropertyValue::Strings(vec!["sifive,clint0", "riscv,clint0"]),
+        )
+        .unwrap();
+    timer
+        .set_prop("reg", PropertyValue::Address(0x200_0000, 0x1_0000))
+        .unwrap();
+
+    let blob = tree.to_bytes().unwrap();
+    let tree = DeviceTree::from_bytes(&blob).unwrap();
+    assert!(tree.root().find_child("memory@0").is_none());
+    assert!(matches!(
+        tree.find_node("/memory@90000000").unwrap().value("reg"),
+        Some(PropertyValue::Address(0x9000_0000, 0x400_0000))
+    ));
+    let timer = tree.find_node("/soc/timer@2000000").unwrap();
+    assert!(matches!(
+        timer.value("reg"),
+        Some(PropertyValue::Address(0x200_0000, 0x1_0000))
+    ));
+    assert!(matches!(
+        timer.value("compatible"),
+        Some(PropertyValue::Strings(it)) if it == ["sifive,clint0", "riscv,clint0"]
+    ));
+}
+
+#[test]
+fn edit_cells() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let root = tree.root_mut();
+    // a single cell no longer fits the address
+    let memory = root.find_child_mut("memory@80000000").unwrap();
+    assert!(memory
+        .set_prop("reg", PropertyValue::Address(0x1_0000_0000, 0x1000))
+        .is_ok());
+    root.set_prop("#address-cells", PropertyValue::Integer(1))
+        .unwrap();
+    let memory = root.find_child_mut("memory@80000000").unwrap();
+    assert!(memory
+        .set_prop("reg", PropertyValue::Address(0x1_0000_0000, 0x1000))
+        .is_err());
+    memory
+        .set_prop("reg", PropertyValue::Address(0x8000_0000, 0x1000))
+        .unwrap();
+    assert!(matches!(
+        memory.of_value("reg"),
+        Some(PropertyValue::Address(0x8000_0000, 0x1000))
+    ));
+}
+
+#[test]
+fn rebuilt_header() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let size_dt_struct = tree.size_dt_struct();
+    tree.edit(|root| {
+        let node = root.add_child(DeviceTreeNode::new("provider"));
+        node.set_prop_raw("phandle", 0x1234u32.to_be_bytes().to_vec());
+    });
+    assert!(tree.size_dt_struct() > size_dt_struct);
+    let blob = tree.to_bytes().unwrap();
+    assert_eq!(tree.total_size(), blob.len());
+    assert_eq!(
+        tree.find_by_phandle(0x1234).map(|node| node.name()),
+        Some("provider")
+    );
+}
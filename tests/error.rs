@@ -0,0 +1,166 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::error::ErrorKind;
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::traits::HasNamedProperty;
+use dtb_parser::{DeviceTree, Fdt};
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn node_and_property() {
+    let source = r#"
+        /dts-v1/;
+        / {
+            soc {
+                uart {
+                    #size-cells = [00 01];
+                };
+            };
+        };
+    "#;
+    let blob = DeviceTree::from_dts(source).unwrap().to_bytes().unwrap();
+    let err = DeviceTree::from_bytes(&blob).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::ParsingFailed);
+    assert_eq!(err.path(), Some("/soc/uart"));
+    assert_eq!(err.property(), Some("#size-cells"));
+    assert_eq!(
+        err.to_string(),
+        "malformed data in /soc/uart, property `#size-cells`"
+    );
+}
+
+#[test]
+fn decoded_value() {
+    let source = r#"
+        /dts-v1/;
+        / {
+            #address-cells = <2>;
+            #size-cells = <1>;
+
+            soc {
+                #address-cells = <1>;
+                #size-cells = <1>;
+
+                uart@10000000 {
+                    reg = <0x10000000 0x100 0x20000000>;
+                };
+            };
+        };
+    "#;
+    let tree = DeviceTree::from_dts(source).unwrap();
+    let uart = tree.find_node("/soc/uart@10000000").unwrap();
+    let reg = uart.find_prop("reg").unwrap();
+    assert!(matches!(reg.value(), PropertyValue::Unknown));
+    let err = reg.try_value().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::ParsingFailed);
+    assert_eq!(err.property(), Some("reg"));
+
+    let err = tree.node_ref(uart).unwrap().try_value("reg").err().unwrap();
+    assert_eq!(err.path(), Some("/soc/uart@10000000"));
+    assert_eq!(err.property(), Some("reg"));
+    assert_eq!(
+        err.to_string(),
+        "malformed data in /soc/uart@10000000, property `reg`"
+    );
+    let soc = tree.node_ref(tree.find_node("/soc").unwrap()).unwrap();
+    assert!(soc.try_value("reg").unwrap().is_none());
+}
+
+#[test]
+fn truncated_ranges() {
+    let source = r#"
+        /dts-v1/;
+        / {
+            #address-cells = <2>;
+            #size-cells = <1>;
+
+            soc {
+                #address-cells = <1>;
+                #size-cells = <1>;
+                ranges = <0x0 0x0 0x10000000 0x1000>;
+                dma-ranges = <0x0 0x0 0x80000000 0x1000 0x1000>;
+            };
+        };
+    "#;
+    let tree = DeviceTree::from_dts(source).unwrap();
+    let soc = tree.node_ref(tree.find_node("/soc").unwrap()).unwrap();
+    assert!(matches!(
+        soc.try_value("ranges"),
+        Ok(Some(PropertyValue::Ranges(ranges))) if ranges == [(0x0, 0x1000_0000, 0x1000)]
+    ));
+    let err = soc.try_value("dma-ranges").err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::ParsingFailed);
+    assert_eq!(err.path(), Some("/soc"));
+    assert_eq!(err.property(), Some("dma-ranges"));
+}
+
+#[test]
+fn blob_offset() {
+    let off_dt_struct = u32::from_be_bytes(DTB[8..12].try_into().unwrap()) as usize;
+    // the first token after the root's name
+    let offset = off_dt_struct + 8;
+    let mut blob = DTB.to_vec();
+    blob[offset..offset + 4].copy_from_slice(&7u32.to_be_bytes());
+
+    let err = Fdt::from_bytes(&blob)
+        .unwrap()
+        .tokens()
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(err, ErrorKind::InvalidToken);
+    assert_eq!(err.offset(), Some(offset));
+
+    let err = DeviceTree::from_bytes(&blob).err().unwrap();
+    assert_eq!(err.offset(), Some(offset));
+    assert_eq!(err.path(), Some("/"));
+    assert_eq!(
+        err.to_string(),
+        format!("invalid token at offset {:#x} in /", offset)
+    );
+
+    let mut blob = DTB.to_vec();
+    blob[0] = 0;
+    let err = Fdt::from_bytes(&blob).err().unwrap();
+    assert_eq!(err.to_string(), "invalid magic number at offset 0x0");
+}
+
+#[test]
+fn source_offset() {
+    let source = "/dts-v1/;\n/ {\n\tprop = <1 2;\n};\n";
+    let err = DeviceTree::from_dts(source).err().unwrap();
+    assert_eq!(err, ErrorKind::InvalidSyntax);
+    assert_eq!(
+        err.offset(),
+        source.find(';').map(|_| source.rfind("2;").unwrap() + 1)
+    );
+}
+
+#[test]
+fn interrupt_context() {
+    let source = r#"
+        /dts-v1/;
+        / {
+            intc: intc {
+                #interrupt-cells = <2>;
+                interrupt-controller;
+            };
+            uart {
+                interrupt-parent = <&intc>;
+                interrupts = <1 2 3>;
+            };
+        };
+    "#;
+    let tree = DeviceTree::from_dts(source).unwrap();
+    let uart = tree.node_ref(tree.find_node("/uart").unwrap()).unwrap();
+    let err = uart.interrupts().err().unwrap();
+    assert_eq!(err.path(), Some("/uart"));
+    assert_eq!(err.property(), Some("interrupts"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_error() {
+    let err: Box<dyn std::error::Error> = Box::new(Fdt::from_bytes(&[]).err().unwrap());
+    assert_eq!(err.to_string(), "not enough data");
+}
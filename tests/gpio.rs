@@ -0,0 +1,124 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::ErrorKind;
+use dtb_parser::gpio::{GpioFlags, HogState};
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    pio: pinctrl@1000 {
+        #pinctrl-cells = <1>;
+    };
+
+    gpio: gpio@2000 {
+        gpio-controller;
+        #gpio-cells = <2>;
+        gpio-line-names = "", "LED", "RESET";
+        gpio-ranges = <&pio 0 32 16>, <&pio 16 0 0>;
+        gpio-ranges-group-names = "", "uart0_grp";
+
+        wifi-enable {
+            gpio-hog;
+            gpios = <4 0>, <5 1>;
+            output-high;
+            line-name = "wifi";
+        };
+
+        button {
+            gpio-hog;
+            gpios = <6 0x10>;
+            input;
+        };
+    };
+
+    device {
+        reset-gpios = <&gpio 2 1>;
+        enable-gpio = <&gpio 3 0x6>;
+        cs-gpios = <&gpio 7 0>, <0>, <&gpio 8 0x22>;
+    };
+};
+"#;
+
+#[test]
+fn specifiers() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let device = tree.node_ref(tree.find_node("/device").unwrap()).unwrap();
+
+    let reset = device.gpios("reset").unwrap();
+    let reset = reset[0].as_ref().unwrap();
+    assert_eq!(reset.controller().name(), "gpio@2000");
+    assert_eq!(reset.line(), 2);
+    assert!(reset.flags().active_low());
+    assert!(!reset.flags().open_drain());
+    assert_eq!(reset.line_name(), Some("RESET"));
+
+    // the legacy singular form
+    let enable = device.gpios("enable").unwrap();
+    let flags = enable[0].as_ref().unwrap().flags();
+    assert!(flags.open_drain());
+    assert!(!flags.open_source());
+    assert!(!flags.active_low());
+    assert_eq!(enable[0].as_ref().unwrap().line_name(), None);
+
+    let cs = device.gpios("cs").unwrap();
+    assert_eq!(cs.len(), 3);
+    assert!(cs[1].is_none());
+    let flags = cs[2].as_ref().unwrap().flags();
+    assert!(flags.pull_down() && flags.open_source() && !flags.pull_up());
+
+    assert!(device.gpios("missing").unwrap().is_empty());
+    assert!(GpioFlags::new(0x10).pull_up());
+}
+
+#[test]
+fn controller() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let gpio = tree
+        .node_ref(tree.find_node("/gpio@2000").unwrap())
+        .unwrap();
+    assert_eq!(gpio.gpio_line_names(), ["", "LED", "RESET"]);
+
+    let hogs = gpio.gpio_hogs().unwrap();
+    assert_eq!(hogs.len(), 2);
+    assert_eq!(hogs[0].state(), HogState::OutputHigh);
+    assert_eq!(hogs[0].line_name(), Some("wifi"));
+    assert_eq!(hogs[0].lines().len(), 2);
+    assert_eq!(hogs[0].lines()[1].line(), 5);
+    assert!(hogs[0].lines()[1].flags().active_low());
+    assert_eq!(hogs[1].state(), HogState::Input);
+    assert!(hogs[1].lines()[0].flags().pull_up());
+
+    let ranges = gpio.gpio_ranges().unwrap();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].pinctrl().name(), "pinctrl@1000");
+    assert_eq!(ranges[0].pin(3), Some(35));
+    assert_eq!(ranges[0].pin(16), None);
+    assert_eq!(ranges[0].group(), None);
+    assert_eq!(ranges[1].count(), 0);
+    assert_eq!(ranges[1].group(), Some("uart0_grp"));
+}
+
+#[test]
+fn malformed_hog() {
+    let source = SOURCE.replace("output-high;", "");
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let gpio = tree
+        .node_ref(tree.find_node("/gpio@2000").unwrap())
+        .unwrap();
+    let err = gpio.gpio_hogs().err().unwrap();
+    assert_eq!(err, ErrorKind::MissingCellParameter);
+    assert_eq!(err.path(), Some("/gpio@2000/wifi-enable"));
+}
+
+#[test]
+fn range_at_the_edge() {
+    let source = SOURCE.replace("<&pio 0 32 16>", "<&pio 0 0xffffffff 4>");
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let gpio = tree
+        .node_ref(tree.find_node("/gpio@2000").unwrap())
+        .unwrap();
+    let ranges = gpio.gpio_ranges().unwrap();
+    assert_eq!(ranges[0].pin(0), Some(u32::MAX));
+    assert_eq!(ranges[0].pin(2), None);
+}
@@ -0,0 +1,136 @@
+use dtb_parser::error::ErrorKind;
+use dtb_parser::walker::{Token, Validation};
+use dtb_parser::Fdt;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+fn with_header(fields: &[(usize, u32)]) -> Vec<u8> {
+    let mut blob = DTB.to_vec();
+    for (offset, value) in fields {
+        blob[*offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+    }
+    blob
+}
+
+fn read_u32(offset: usize) -> u32 {
+    u32::from_be_bytes(DTB[offset..offset + 4].try_into().unwrap())
+}
+
+/// A blob in the layout of versions before 16: nodes named by their full path
+/// and a `reg` value of 8 bytes aligned on 8 bytes
+fn old_blob(version: u32) -> Vec<u8> {
+    let header_size = match version {
+        1 => 28,
+        2 => 32,
+        _ => 36,
+    };
+    let mut structs = Vec::new();
+    let mut push = |bytes: &[u8]| structs.extend_from_slice(bytes);
+    push(&1u32.to_be_bytes());
+    push(b"/\0\0\0");
+    push(&3u32.to_be_bytes());
+    push(&8u32.to_be_bytes());
+    push(&0u32.to_be_bytes());
+    push(&[0; 4]);
+    push(&0x1000u64.to_be_bytes());
+    push(&1u32.to_be_bytes());
+    push(b"/cpus\0\0\0");
+    push(&2u32.to_be_bytes());
+    push(&2u32.to_be_bytes());
+    push(&9u32.to_be_bytes());
+    let strings = b"reg\0";
+
+    let off_mem_reserved = 40u32;
+    let off_dt_struct = off_mem_reserved + 16;
+    let off_dt_strings = off_dt_struct + structs.len() as u32;
+    let total_size = off_dt_strings + strings.len() as u32;
+    let fields = [
+        0xd00dfeed,
+        total_size,
+        off_dt_struct,
+        off_dt_strings,
+        off_mem_reserved,
+        version,
+        1,
+        0,
+        strings.len() as u32,
+    ];
+    let mut blob: Vec<u8> = fields.iter().flat_map(|f| f.to_be_bytes()).collect();
+    blob.truncate(header_size);
+    blob.resize(off_dt_struct as usize, 0);
+    blob.extend_from_slice(&structs);
+    blob.extend_from_slice(strings);
+    blob
+}
+
+#[test]
+fn strict() {
+    assert!(Fdt::from_bytes_with(DTB, Validation::Strict).is_ok());
+
+    let off_dt_struct = read_u32(8);
+    let off_mem_reserved = read_u32(16);
+    for (blob, kind, offset) in [
+        (
+            with_header(&[(20, 16), (24, 17)]),
+            ErrorKind::UnsupportedVersion,
+            20,
+        ),
+        (
+            with_header(&[(12, off_dt_struct)]),
+            ErrorKind::OverlappingBlocks,
+            8,
+        ),
+        (
+            with_header(&[(16, off_dt_struct)]),
+            ErrorKind::OverlappingBlocks,
+            16,
+        ),
+        (
+            with_header(&[(16, off_mem_reserved + 4)]),
+            ErrorKind::MisalignedBlock,
+            16,
+        ),
+    ] {
+        let fdt = Fdt::from_bytes(&blob).unwrap();
+        let err = fdt.validate(Validation::Strict).err().unwrap();
+        assert_eq!(err, kind);
+        assert_eq!(err.offset(), Some(offset));
+        assert!(Fdt::from_bytes_with(&blob, Validation::Strict).is_err());
+    }
+
+    // both modes refuse a layout newer than the parser knows
+    let blob = with_header(&[(20, 18), (24, 18)]);
+    for validation in [Validation::Lenient, Validation::Strict] {
+        let err = Fdt::from_bytes_with(&blob, validation).err().unwrap();
+        assert_eq!(err, ErrorKind::UnsupportedVersion);
+        assert_eq!(err.offset(), Some(24));
+    }
+}
+
+#[test]
+fn old_versions() {
+    for version in [1, 2, 3, 15] {
+        let blob = old_blob(version);
+        let fdt = Fdt::from_bytes_with(&blob, Validation::Strict).unwrap();
+        assert_eq!(fdt.version(), version as usize);
+        assert_eq!(fdt.boot_cpu_id(), 0);
+
+        let root = fdt.root().unwrap();
+        assert_eq!(root.name(), "");
+        assert_eq!(root.find_prop("reg").unwrap().as_u64(), Some(0x1000));
+        assert!(fdt.find_node("/cpus").is_some());
+        assert!(matches!(fdt.tokens().last(), Some(Ok(Token::End))));
+
+        // the header of version 1 stops before the boot cpu id
+        assert!(Fdt::from_bytes(&blob[..20]).is_err());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn old_version_tree() {
+    let blob = old_blob(15);
+    let tree = dtb_parser::DeviceTree::from_bytes(&blob).unwrap();
+    assert_eq!(tree.version(), 15);
+    assert!(tree.find_node("/cpus").is_some());
+}
@@ -1,4 +1,8 @@
+#![cfg(feature = "alloc")]
+#![allow(clippy::redundant_pattern_matching)]
+
 use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::prop::PropertyValue;
 use dtb_parser::traits::{HasNamedChildNode, HasNamedProperty};
 
 const DTB: &[u8] = include_bytes!("device.dtb");
@@ -42,4 +46,19 @@ fn type_name(){
     let tree = DeviceTree::from_bytes(DTB).unwrap();
     let node = tree.find_node("/cpus/cpu@0").unwrap();
     assert_eq!(node.type_name(), "cpu");
-}
\ No newline at end of file
+}
+
+#[test]
+fn borrowed_values(){
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let blob = DTB.as_ptr_range();
+    let node = tree.find_node("/soc/uart@10000000").unwrap();
+    assert!(blob.contains(&node.name().as_ptr()));
+    match node.find_prop("compatible").unwrap().value() {
+        PropertyValue::String(s) => {
+            assert_eq!(s, "ns16550a");
+            assert!(blob.contains(&s.as_ptr()));
+        }
+        _ => panic!("compatible should be a string"),
+    }
+}
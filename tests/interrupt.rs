@@ -0,0 +1,230 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::error::ErrorKind;
+use dtb_parser::interrupt::{GicInterrupt, GicKind, Trigger};
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn fixture_interrupts() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let uart = tree.find_node("/soc/uart@10000000").unwrap();
+    let uart = tree.node_ref(uart).unwrap();
+    let interrupts = uart.interrupts().unwrap();
+    assert_eq!(interrupts.len(), 1);
+    assert_eq!(
+        interrupts[0].controller().path(),
+        "/cpus/cpu@0/interrupt-controller"
+    );
+    assert_eq!(interrupts[0].cells(), [0x0a]);
+    assert_eq!(interrupts[0].number(), Some(0x0a));
+    assert_eq!(interrupts[0].plic(), Some(0x0a));
+    assert!(interrupts[0].gic().is_none());
+
+    let memory = tree.find_node("/memory@80000000").unwrap();
+    let memory = tree.node_ref(memory).unwrap();
+    assert!(memory.interrupts().unwrap().is_empty());
+
+    let foreign = DeviceTree::from_bytes(DTB).unwrap();
+    let uart = foreign.find_node("/soc/uart@10000000").unwrap();
+    assert!(matches!(
+        tree.interrupts(uart),
+        Err(err) if err == ErrorKind::ParsingFailed
+    ));
+}
+
+const GIC: &str = r#"
+    /dts-v1/;
+    / {
+        interrupt-parent = <&gic>;
+
+        gic: interrupt-controller@8000000 {
+            interrupt-controller;
+            #interrupt-cells = <3>;
+        };
+
+        pic: pic {
+            interrupt-controller;
+            #interrupt-cells = <2>;
+            interrupt-parent = <&gic>;
+            interrupts = <0 5 4>;
+        };
+
+        soc {
+            timer {
+                interrupts = <1 13 0xf08>, <1 14 0xf08>;
+            };
+
+            uart {
+                interrupts-extended = <&gic 0 1 4>, <&pic 3 2>;
+            };
+
+            broken {
+                interrupt-parent = <0x40>;
+                interrupts = <1>;
+            };
+
+            short {
+                interrupts = <0 1>;
+            };
+        };
+    };
+"#;
+
+#[test]
+fn inherited_parent() {
+    let tree = DeviceTree::from_dts(GIC).unwrap();
+    let timer = tree.find_node("/soc/timer").unwrap();
+    let timer = tree.node_ref(timer).unwrap();
+    assert_eq!(
+        timer.interrupt_parent().unwrap().unwrap().path(),
+        "/interrupt-controller@8000000"
+    );
+    let interrupts = timer.interrupts().unwrap();
+    assert_eq!(interrupts.len(), 2);
+    let ppi = interrupts[1].gic().unwrap();
+    assert_eq!(
+        ppi,
+        GicInterrupt {
+            kind: GicKind::Ppi,
+            number: 14,
+            trigger: Some(Trigger::LevelLow),
+        }
+    );
+    assert_eq!(ppi.irq(), 30);
+    assert!(interrupts[1].number().is_none());
+}
+
+#[test]
+fn extended_interrupts() {
+    let tree = DeviceTree::from_dts(GIC).unwrap();
+    let uart = tree.find_node("/soc/uart").unwrap();
+    let interrupts = tree.interrupts(uart).unwrap();
+    assert_eq!(interrupts.len(), 2);
+    let spi = interrupts[0].gic().unwrap();
+    assert_eq!(spi.kind, GicKind::Spi);
+    assert_eq!(spi.irq(), 33);
+    assert_eq!(spi.trigger, Some(Trigger::LevelHigh));
+    assert_eq!(interrupts[1].controller().path(), "/pic");
+    assert_eq!(interrupts[1].number(), Some(3));
+    assert_eq!(interrupts[1].trigger(), Some(Trigger::EdgeFalling));
+}
+
+#[test]
+fn bad_interrupts() {
+    let tree = DeviceTree::from_dts(GIC).unwrap();
+    let broken = tree.find_node("/soc/broken").unwrap();
+    assert!(matches!(
+        tree.node_ref(broken).unwrap().interrupts(),
+        Err(err) if err == ErrorKind::UnresolvedPHandle
+    ));
+    let short = tree.find_node("/soc/short").unwrap();
+    assert!(matches!(
+        tree.node_ref(short).unwrap().interrupts(),
+        Err(err) if err == ErrorKind::ParsingFailed
+    ));
+}
+
+const NEXUS: &str = r#"
+    /dts-v1/;
+    / {
+        #address-cells = <1>;
+
+        gic: interrupt-controller {
+            interrupt-controller;
+            #address-cells = <0>;
+            #interrupt-cells = <3>;
+        };
+
+        bridge: bridge {
+            #address-cells = <1>;
+            #interrupt-cells = <1>;
+            interrupt-map-mask = <0 7>;
+            interrupt-map = <0 1 &gic 0 40 4>, <0 2 &gic 0 41 4>;
+        };
+
+        pci {
+            #address-cells = <3>;
+            #interrupt-cells = <1>;
+            interrupt-map-mask = <0x1800 0 0 7>;
+            interrupt-map = <0x0000 0 0 1 &gic 0 10 4>,
+                            <0x0800 0 0 1 &bridge 0x10 2>;
+
+            device@1,0 {
+                reg = <0x0800 0 0 0 0>;
+                interrupts = <1>;
+            };
+
+            device@2,0 {
+                reg = <0x1000 0 0 0 0>;
+                interrupts = <1>;
+            };
+        };
+
+        combiner: combiner {
+            interrupt-controller;
+            #address-cells = <0>;
+            #interrupt-cells = <1>;
+            interrupt-map = <1 &gic 0 50 4>;
+        };
+
+        sensor {
+            interrupts-extended = <&combiner 1>, <&combiner 2>;
+        };
+    };
+"#;
+
+#[test]
+fn map_interrupt() {
+    let tree = DeviceTree::from_dts(NEXUS).unwrap();
+    let pci = tree.find_node("/pci").unwrap();
+
+    // device 0, INTA, bus and function bits are masked off
+    let mapped = tree
+        .map_interrupt(pci, &[0x0300, 0, 0], &[1])
+        .unwrap()
+        .unwrap();
+    assert_eq!(mapped.controller().path(), "/interrupt-controller");
+    assert_eq!(mapped.gic().unwrap().irq(), 42);
+
+    // device 1 goes through the bridge first
+    let mapped = tree
+        .map_interrupt(pci, &[0x0800, 0, 0], &[1])
+        .unwrap()
+        .unwrap();
+    assert_eq!(mapped.cells(), [0, 41, 4]);
+
+    assert!(tree
+        .map_interrupt(pci, &[0x1000, 0, 0], &[1])
+        .unwrap()
+        .is_none());
+    assert!(matches!(
+        tree.map_interrupt(pci, &[0], &[1]),
+        Err(err) if err == ErrorKind::ParsingFailed
+    ));
+}
+
+#[test]
+fn behind_nexus() {
+    let tree = DeviceTree::from_dts(NEXUS).unwrap();
+    let device = tree.find_node("/pci/device@1,0").unwrap();
+    let interrupts = tree.node_ref(device).unwrap().interrupts().unwrap();
+    assert_eq!(interrupts.len(), 1);
+    assert_eq!(interrupts[0].controller().path(), "/interrupt-controller");
+    assert_eq!(interrupts[0].cells(), [0, 41, 4]);
+
+    // the map of a controller only takes the interrupts it has a row for
+    let sensor = tree.find_node("/sensor").unwrap();
+    let interrupts = tree.node_ref(sensor).unwrap().interrupts().unwrap();
+    assert_eq!(interrupts[0].controller().path(), "/interrupt-controller");
+    assert_eq!(interrupts[0].gic().unwrap().irq(), 82);
+    assert_eq!(interrupts[1].controller().path(), "/combiner");
+    assert_eq!(interrupts[1].cells(), [2]);
+
+    let unmapped = tree.find_node("/pci/device@2,0").unwrap();
+    let err = tree.node_ref(unmapped).unwrap().interrupts().err().unwrap();
+    assert_eq!(err, ErrorKind::ParsingFailed);
+    assert_eq!(err.path(), Some("/pci/device@2,0"));
+    assert_eq!(err.property(), Some("interrupts"));
+}
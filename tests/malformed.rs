@@ -0,0 +1,255 @@
+//! Truncated and corrupted versions of the fixture must be rejected or parsed, never panic
+
+use dtb_parser::error::ErrorKind;
+use dtb_parser::walker::{Token, Validation};
+use dtb_parser::Fdt;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+fn read_u32(blob: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(blob[offset..offset + 4].try_into().unwrap())
+}
+
+fn write_u32(blob: &mut [u8], offset: usize, value: u32) {
+    blob[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+fn with_header(offset: usize, value: u32) -> Vec<u8> {
+    let mut blob = DTB.to_vec();
+    write_u32(&mut blob, offset, value);
+    blob
+}
+
+/// Goes through everything that reads the blob
+fn exercise(blob: &[u8]) {
+    for validation in [Validation::Lenient, Validation::Strict] {
+        let _ = Fdt::from_bytes_with(blob, validation);
+    }
+    if let Ok(fdt) = Fdt::from_bytes(blob) {
+        let _ = fdt.validate(Validation::Strict);
+        fdt.memory_reservations().for_each(drop);
+        for token in fdt.tokens() {
+            if let Ok(Token::Property(prop)) = token {
+                prop.as_u32();
+                prop.as_u64();
+                prop.as_str();
+                prop.as_str_list().for_each(drop);
+                prop.as_cells().into_iter().flatten().for_each(drop);
+            }
+        }
+        if let Ok(root) = fdt.root() {
+            for child in root.children().flatten() {
+                child.props().for_each(drop);
+                child.address_cells();
+                child.size_cells();
+                if let Some(reg) = child.reg() {
+                    reg.for_each(drop);
+                }
+            }
+        }
+        fdt.find_node("/soc/uart@10000000");
+    }
+
+    #[cfg(feature = "alloc")]
+    if let Ok(tree) = dtb_parser::DeviceTree::from_bytes(blob) {
+        exercise_tree(&tree);
+        let bytes = tree.to_bytes().unwrap();
+        assert!(dtb_parser::DeviceTree::from_bytes(&bytes).is_ok());
+    }
+}
+
+/// Goes through every lookup of the tree, on every node
+#[cfg(feature = "alloc")]
+fn exercise_tree(tree: &dtb_parser::DeviceTree) {
+    use dtb_parser::traits::HasNamedProperty;
+
+    for node in tree {
+        for prop in node.props() {
+            let _ = prop.value().to_string();
+            let _ = prop.reg_entries();
+            prop.resolve_phandle(tree);
+            prop.as_u32();
+            prop.as_u64();
+            prop.as_str();
+            prop.as_str_list().for_each(drop);
+            prop.as_cells().into_iter().flatten().for_each(drop);
+            prop.as_bytes();
+        }
+        node.path();
+        node.ancestors().for_each(drop);
+        node.is_compatible("simple-bus");
+        node.is_available();
+        if let Some(phandle) = node.phandle() {
+            tree.find_by_phandle(phandle);
+        }
+        tree.node_ref(node.node());
+
+        let _ = node.interrupt_parent();
+        let _ = node.interrupts();
+        node.find_prop("interrupt-map")
+            .map(|_| tree.map_interrupt(node.node(), &[0, 0, 0], &[1]));
+        tree.translate_address(node.node(), 0);
+        tree.cpu_regs(node.node());
+        tree.dma_to_cpu(node.node(), 0);
+        tree.cpu_to_dma(node.node(), 0);
+        tree.dma_coherent(node.node());
+        tree.dma_windows(node.node());
+
+        node.phandle_args("clocks", "#clock-cells").for_each(drop);
+        let _ = node.phandle_args_by_name("clocks", "#clock-cells", "bus");
+        if let Ok(clocks) = node.clocks() {
+            for clock in clocks {
+                let _ = clock.provider().rate();
+                clock.output_name();
+            }
+        }
+        let _ = node.clock("bus");
+        let _ = node.assigned_clocks();
+
+        for name in ["", "reset"] {
+            if let Ok(gpios) = node.gpios(name) {
+                for gpio in gpios.iter().flatten() {
+                    gpio.line_name();
+                }
+            }
+        }
+        node.gpio_line_names();
+        let _ = node.gpio_hogs();
+        if let Ok(ranges) = node.gpio_ranges() {
+            for range in ranges {
+                range.pin(u32::MAX);
+            }
+        }
+
+        let _ = node.pinctrl_states();
+        let _ = node.pinctrl_state("default");
+        let _ = node.pin_config();
+        let _ = node.memory_regions();
+    }
+    for provider in tree.clock_providers() {
+        provider.output_names();
+        let _ = provider.rate();
+    }
+    if let Ok(reserved) = tree.reserved_memory() {
+        let _ = reserved.static_ranges();
+        reserved.dynamic_regions().for_each(drop);
+    }
+    let _ = tree.memory_map();
+    let _ = tree.to_string();
+    let _ = tree.to_dts();
+}
+
+/// The seed corpus of the fuzz targets, truncated and corrupted versions of the fixture
+#[test]
+fn seed_corpus() {
+    let mut seeds = 0;
+    for target in ["device_tree", "walker"] {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/corpus/").to_string() + target;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            exercise(&std::fs::read(entry.unwrap().path()).unwrap());
+            seeds += 1;
+        }
+    }
+    assert!(seeds > 0);
+}
+
+#[test]
+fn truncated() {
+    for len in 0..DTB.len() {
+        exercise(&DTB[..len]);
+    }
+}
+
+#[test]
+fn corrupted() {
+    for offset in 0..DTB.len() {
+        for corrupt in [|_| 0xff, |b| b ^ 0x01] {
+            let mut blob = DTB.to_vec();
+            blob[offset] = corrupt(blob[offset]);
+            exercise(&blob);
+        }
+    }
+}
+
+#[test]
+fn corrupted_cells() {
+    for offset in (0..DTB.len() - 3).step_by(4) {
+        for value in [0, 1, 0xffff_fffc, u32::MAX] {
+            let mut blob = DTB.to_vec();
+            write_u32(&mut blob, offset, value);
+            exercise(&blob);
+        }
+    }
+}
+
+#[test]
+fn lying_header() {
+    let total_size = read_u32(DTB, 4);
+    let off_dt_struct = read_u32(DTB, 8);
+    let off_dt_strings = read_u32(DTB, 12);
+    let size_dt_strings = read_u32(DTB, 32);
+    let size_dt_struct = read_u32(DTB, 36);
+
+    assert!(matches!(
+        Fdt::from_bytes(&DTB[..39]),
+        Err(err) if err == ErrorKind::NotEnoughLength
+    ));
+    assert!(matches!(
+        Fdt::from_bytes(&with_header(4, total_size + 1)),
+        Err(err) if err == ErrorKind::NotEnoughLength
+    ));
+    for blob in [
+        with_header(8, total_size.next_multiple_of(4)),
+        with_header(12, u32::MAX),
+        with_header(32, size_dt_strings + 1),
+        with_header(36, u32::MAX),
+    ] {
+        assert!(matches!(
+            Fdt::from_bytes(&blob),
+            Err(err) if err == ErrorKind::BlockOutOfBounds
+        ));
+    }
+    assert!(matches!(
+        Fdt::from_bytes(&with_header(8, off_dt_struct + 2)),
+        Err(err) if err == ErrorKind::MisalignedBlock
+    ));
+
+    // the FDT_END token is cut off by the size of the structure block
+    let blob = with_header(36, size_dt_struct - 4);
+    let fdt = Fdt::from_bytes(&blob).unwrap();
+    assert!(matches!(
+        fdt.tokens().last(),
+        Some(Err(err)) if err == ErrorKind::NotEnoughLength
+    ));
+
+    // names of the strings block must be terminated within it
+    let blob = with_header(32, size_dt_strings - 1);
+    let fdt = Fdt::from_bytes(&blob).unwrap();
+    assert!(fdt.tokens().any(|token| token.is_err()));
+    assert!(off_dt_strings + size_dt_strings <= total_size);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn deep_nesting() {
+    use dtb_parser::node::DeviceTreeNode;
+    use dtb_parser::DeviceTree;
+
+    // the source is held to the same limit, so the nodes are attached by hand
+    fn nested(depth: usize) -> Vec<u8> {
+        let mut tree = DeviceTree::from_dts("/dts-v1/; / { };").unwrap();
+        let mut node = DeviceTreeNode::new("n");
+        for _ in 1..depth {
+            let mut parent = DeviceTreeNode::new("n");
+            parent.add_child(node);
+            node = parent;
+        }
+        tree.root_mut().add_child(node);
+        tree.to_bytes().unwrap()
+    }
+    assert!(DeviceTree::from_bytes(&nested(64)).is_ok());
+    assert!(matches!(
+        DeviceTree::from_bytes(&nested(65)),
+        Err(err) if err == ErrorKind::ParsingFailed
+    ));
+}
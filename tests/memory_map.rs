@@ -0,0 +1,105 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::ErrorKind;
+use dtb_parser::memory_map::MemoryRange;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/memreserve/ 0x80000000 0x10000;
+/ {
+    #address-cells = <2>;
+    #size-cells = <2>;
+
+    memory@80000000 {
+        device_type = "memory";
+        reg = <0x0 0x80000000 0x0 0x20000000>, <0x0 0xa0000000 0x0 0x20000000>;
+        numa-node-id = <0>;
+    };
+
+    dram1 {
+        device_type = "memory";
+        reg = <0x1 0x00000000 0x0 0x40000000>;
+        numa-node-id = <1>;
+    };
+
+    memory@c0000000 {
+        device_type = "memory";
+        reg = <0x0 0xc0000000 0x0 0x1000000>;
+        numa-node-id = <1>;
+        status = "disabled";
+    };
+
+    reserved-memory {
+        #address-cells = <2>;
+        #size-cells = <2>;
+        ranges;
+
+        firmware@90000000 {
+            reg = <0x0 0x90000000 0x0 0x100000>;
+            no-map;
+        };
+
+        ramoops@a0000000 {
+            reg = <0x0 0xa0000000 0x0 0x100000>;
+        };
+
+        tail@13ff00000 {
+            reg = <0x1 0x3ff00000 0x0 0x200000>;
+            no-map;
+        };
+    };
+};
+"#;
+
+const fn range(start: u64, size: u64, numa_node: u32) -> MemoryRange {
+    MemoryRange {
+        start,
+        size,
+        numa_node: Some(numa_node),
+    }
+}
+
+#[test]
+fn memory_map() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let map = tree.memory_map().unwrap();
+    assert_eq!(
+        map,
+        [
+            // the header reservation and the firmware are cut out, ramoops is mapped
+            range(0x8001_0000, 0x0fff_0000, 0),
+            range(0x9010_0000, 0x2ff0_0000, 0),
+            // the no-map region runs past the end of the memory
+            range(0x1_0000_0000, 0x3ff0_0000, 1),
+        ]
+    );
+    assert_eq!(map[1].end(), 0xc000_0000);
+}
+
+#[test]
+fn malformed_memory() {
+    let source = SOURCE.replace(
+        "reg = <0x1 0x00000000 0x0 0x40000000>;",
+        "reg = <0x1 0x00000000 0x0>;",
+    );
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let err = tree.memory_map().err().unwrap();
+    assert_eq!(err, ErrorKind::ParsingFailed);
+    assert_eq!(err.path(), Some("/dram1"));
+    assert_eq!(err.property(), Some("reg"));
+}
+
+#[test]
+fn malformed_reserved_region() {
+    // the firmware cannot be cut out without its size
+    let source = SOURCE.replace(
+        "reg = <0x0 0x90000000 0x0 0x100000>;",
+        "reg = <0x0 0x90000000 0x0>;",
+    );
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let err = tree.memory_map().err().unwrap();
+    assert_eq!(err, ErrorKind::ParsingFailed);
+    assert_eq!(err.path(), Some("/reserved-memory/firmware@90000000"));
+    assert_eq!(err.property(), Some("reg"));
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn iterate_with_parents() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let mut nodes = tree.into_iter();
+    let root = nodes.next().unwrap();
+    assert_eq!(root.path(), "/");
+    assert_eq!(root.depth(), 0);
+    assert!(root.parent().is_none());
+
+    for node in nodes {
+        let parent = node.parent().unwrap();
+        assert!(parent
+            .nodes()
+            .iter()
+            .any(|it| core::ptr::eq(it, node.node())));
+        assert_eq!(node.depth(), parent.depth() + 1);
+        assert_eq!(node.ancestors().count(), node.depth());
+        assert_eq!(
+            tree.find_node(&node.path()).unwrap() as *const _,
+            node.node() as *const _
+        );
+    }
+}
+
+#[test]
+fn node_ref() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let core = tree.find_node("/cpus/cpu-map/cluster0/core0").unwrap();
+    let core = tree.node_ref(core).unwrap();
+    assert_eq!(core.path(), "/cpus/cpu-map/cluster0/core0");
+    assert_eq!(core.depth(), 4);
+    let names: Vec<_> = core
+        .ancestors()
+        .map(|node| node.name().to_string())
+        .collect();
+    assert_eq!(names, ["cluster0", "cpu-map", "cpus", ""]);
+
+    let other = DeviceTree::from_bytes(DTB).unwrap();
+    assert!(tree.node_ref(other.root()).is_none());
+}
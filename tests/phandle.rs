@@ -0,0 +1,112 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::node::DeviceTreeNode;
+use dtb_parser::traits::HasNamedProperty;
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn find_by_phandle() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    assert_eq!(tree.find_by_phandle(1).unwrap().name(), "cpu@0");
+    let intc = tree.find_by_phandle(2).unwrap();
+    assert_eq!(intc.name(), "interrupt-controller");
+    assert_eq!(intc.phandle(), Some(2));
+    assert_eq!(tree.find_by_phandle(3).unwrap().name(), "gpio-controller1");
+    assert!(tree.find_by_phandle(5).is_none());
+
+    let uart = tree.find_node("/soc/uart@10000000").unwrap();
+    let parent = uart.find_prop("interrupt-parent").unwrap();
+    assert_eq!(
+        parent.resolve_phandle(&tree).unwrap().name(),
+        "interrupt-controller"
+    );
+    let cpu = tree.find_node("/cpus/cpu-map/cluster0/core0").unwrap();
+    let cpu = cpu
+        .find_prop("cpu")
+        .unwrap()
+        .resolve_phandle(&tree)
+        .unwrap();
+    assert_eq!(cpu.name(), "cpu@0");
+    let compatible = uart.find_prop("compatible").unwrap();
+    assert!(compatible.resolve_phandle(&tree).is_none());
+}
+
+#[test]
+fn legacy_and_edited() {
+    let mut tree = DeviceTree::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            old { linux,phandle = <5>; };
+            new { phandle = <6>; };
+        };
+        "#,
+    )
+    .unwrap();
+    assert_eq!(tree.find_by_phandle(5).unwrap().name(), "old");
+
+    // the index goes stale once nodes move around
+    tree.root_mut().remove_child("old");
+    assert!(tree.find_by_phandle(5).is_none());
+    assert_eq!(tree.find_by_phandle(6).unwrap().name(), "new");
+    tree.root_mut()
+        .find_child_mut("new")
+        .unwrap()
+        .set_prop_raw("phandle", 7u32.to_be_bytes().to_vec());
+    assert!(tree.find_by_phandle(6).is_none());
+    assert_eq!(tree.find_by_phandle(7).unwrap().name(), "new");
+}
+
+fn cells(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect()
+}
+
+#[test]
+fn large_tree() {
+    // each lookup follows the index, a scan of the tree per phandle would take minutes here
+    const BUSES: u32 = 200;
+    const PER_BUS: u32 = 100;
+    let mut tree =
+        DeviceTree::from_dts("/dts-v1/; / { #address-cells = <1>; #size-cells = <1>; };").unwrap();
+    let mut phandles = Vec::new();
+    for bus in 0..BUSES {
+        let mut node = DeviceTreeNode::new(format!("bus{bus}"));
+        node.set_prop_raw("#address-cells", cells(&[1]));
+        node.set_prop_raw("#size-cells", cells(&[1]));
+        node.set_prop_raw("ranges", Vec::new());
+        let node = tree.root_mut().add_child(node);
+        for i in 0..PER_BUS {
+            let phandle = bus * PER_BUS + i + 1;
+            let clock = node.add_child(DeviceTreeNode::new(format!("clock{i}")));
+            clock.set_prop_raw("phandle", cells(&[phandle]));
+            clock.set_prop_raw("#clock-cells", cells(&[0]));
+            let memory = node.add_child(DeviceTreeNode::new(format!("memory@{phandle:x}000")));
+            memory.set_prop_raw("device_type", b"memory\0".to_vec());
+            memory.set_prop_raw("reg", cells(&[phandle << 16, 0x1000]));
+            phandles.push(phandle);
+        }
+    }
+    tree.root_mut()
+        .add_child(DeviceTreeNode::new("consumer"))
+        .set_prop_raw("clocks", cells(&phandles));
+    let blob = tree.to_bytes().unwrap();
+
+    let tree = DeviceTree::from_bytes(&blob).unwrap();
+    let consumer = tree.node_ref(tree.find_node("/consumer").unwrap()).unwrap();
+    let clocks = consumer.clocks().unwrap();
+    assert_eq!(clocks.len(), phandles.len());
+    assert_eq!(
+        clocks[phandles.len() - 1].provider().node().path(),
+        format!("/bus{}/clock{}", BUSES - 1, PER_BUS - 1)
+    );
+    assert_eq!(tree.memory_map().unwrap().len(), phandles.len());
+    // the providers of the phandle lists are looked up in a table as the source is written
+    let dts = tree.to_dts();
+    assert!(dts.contains("clocks = <0x01 0x02 0x03 "));
+    assert!(dts.contains(&format!(" {:#x}>;", BUSES * PER_BUS)));
+}
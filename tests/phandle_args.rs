@@ -0,0 +1,106 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::ErrorKind;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    osc: oscillator {
+        #clock-cells = <0>;
+    };
+
+    cc: clock-controller {
+        #clock-cells = <1>;
+    };
+
+    rst: reset-controller {
+        #reset-cells = <2>;
+    };
+
+    device {
+        clocks = <&cc 3>, <&osc>;
+        clock-names = "bus", "ref";
+        resets = <0>, <&rst 1 2>;
+        reset-names = "unused", "core";
+        dmas = <&osc 1>;
+        pwms = <&cc>;
+    };
+};
+"#;
+
+#[test]
+fn phandle_args() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let device = tree.find_node("/device").unwrap();
+
+    let clocks = tree
+        .phandle_args(device, "clocks", "#clock-cells")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let clocks = clocks.iter().flatten().collect::<Vec<_>>();
+    assert_eq!(clocks.len(), 2);
+    assert_eq!(clocks[0].provider().name(), "clock-controller");
+    assert_eq!(clocks[0].args(), &[3]);
+    assert_eq!(clocks[1].provider().name(), "oscillator");
+    assert!(clocks[1].args().is_empty());
+
+    let bus = tree
+        .phandle_args_by_name(device, "clocks", "#clock-cells", "bus")
+        .unwrap()
+        .unwrap();
+    assert_eq!(bus.args(), &[3]);
+    assert!(tree
+        .phandle_args_by_name(device, "clocks", "#clock-cells", "apb")
+        .unwrap()
+        .is_none());
+    assert_eq!(tree.phandle_args(device, "gpios", "#gpio-cells").count(), 0);
+
+    // an empty entry keeps its place
+    let device = tree.node_ref(device).unwrap();
+    assert!(device
+        .phandle_args("resets", "#reset-cells")
+        .next()
+        .unwrap()
+        .unwrap()
+        .is_none());
+    let core = device
+        .phandle_args_by_name("resets", "#reset-cells", "core")
+        .unwrap()
+        .unwrap();
+    assert_eq!(core.provider().path(), "/reset-controller");
+    assert_eq!(core.args(), &[1, 2]);
+}
+
+#[test]
+fn malformed_lists() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let device = tree.find_node("/device").unwrap();
+
+    // the oscillator does not provide dma channels
+    let mut dmas = tree.phandle_args(device, "dmas", "#dma-cells");
+    let err = dmas.next().unwrap().err().unwrap();
+    assert_eq!(err, ErrorKind::MissingCellParameter);
+    assert_eq!(err.property(), Some("dmas"));
+    assert!(dmas.next().is_none());
+
+    // the argument of the clock controller is missing
+    let mut pwms = tree.phandle_args(device, "pwms", "#clock-cells");
+    assert!(matches!(pwms.next(), Some(Err(err)) if err == ErrorKind::ParsingFailed));
+}
+
+#[test]
+fn broken_entry_before_name() {
+    let source = SOURCE.replace("<&cc 3>, <&osc>", "<0x40 3>, <&osc>");
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let device = tree.node_ref(tree.find_node("/device").unwrap()).unwrap();
+
+    // the entry named `ref` cannot be found once the one before it fails
+    let err = device
+        .phandle_args_by_name("clocks", "#clock-cells", "ref")
+        .err()
+        .unwrap();
+    assert_eq!(err, ErrorKind::UnresolvedPHandle);
+    assert_eq!(err.path(), Some("/device"));
+    assert_eq!(err.property(), Some("clocks"));
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::ErrorKind;
+use dtb_parser::pinctrl::Bias;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    pinctrl@1000 {
+        uart0_default: uart0-default {
+            pins = "PA0", "PA1";
+            function = "uart0";
+            bias-pull-up;
+            drive-strength = <8>;
+        };
+
+        uart0_cts: uart0-cts {
+            groups = "uart0_cts_grp";
+            function = "uart0";
+            bias-pull-down = <47000>;
+        };
+
+        uart0_sleep: uart0-sleep {
+            pinmux = <0x1001>, <0x1002>;
+            bias-disable;
+        };
+
+        broken: broken {
+            drive-strength = "strong";
+        };
+    };
+
+    uart {
+        pinctrl-names = "default", "sleep";
+        pinctrl-0 = <&uart0_default &uart0_cts>;
+        pinctrl-1 = <&uart0_sleep>;
+        pinctrl-3 = <&uart0_sleep>;
+    };
+
+    spi {
+        pinctrl-0 = <&broken>;
+    };
+};
+"#;
+
+#[test]
+fn states() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let uart = tree.node_ref(tree.find_node("/uart").unwrap()).unwrap();
+
+    // pinctrl-3 is out of the sequence
+    let states = uart.pinctrl_states().unwrap();
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].name(), "default");
+    assert_eq!(states[1].id(), 1);
+
+    let default = uart.pinctrl_state("default").unwrap().unwrap();
+    let configs = default
+        .configs()
+        .iter()
+        .map(|config| config.name())
+        .collect::<Vec<_>>();
+    assert_eq!(configs, ["uart0-default", "uart0-cts"]);
+    assert!(uart.pinctrl_state("idle").unwrap().is_none());
+
+    // without names a state is named by its id
+    let spi = tree.node_ref(tree.find_node("/spi").unwrap()).unwrap();
+    assert_eq!(spi.pinctrl_states().unwrap()[0].name(), "0");
+}
+
+#[test]
+fn pin_config() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let config = |path| tree.find_node(path).unwrap().pin_config();
+
+    let default = config("/pinctrl@1000/uart0-default").unwrap();
+    assert_eq!(default.pins, ["PA0", "PA1"]);
+    assert_eq!(default.function, Some("uart0"));
+    assert_eq!(default.bias, Some(Bias::PullUp(None)));
+    assert_eq!(default.drive_strength, Some(8));
+
+    let cts = config("/pinctrl@1000/uart0-cts").unwrap();
+    assert_eq!(cts.groups, ["uart0_cts_grp"]);
+    assert_eq!(cts.bias, Some(Bias::PullDown(Some(47000))));
+    assert!(cts.pins.is_empty());
+
+    let sleep = config("/pinctrl@1000/uart0-sleep").unwrap();
+    assert_eq!(sleep.pinmux, [0x1001, 0x1002]);
+    assert_eq!(sleep.bias, Some(Bias::Disable));
+    assert_eq!(sleep.function, None);
+
+    let err = config("/pinctrl@1000/broken").err().unwrap();
+    assert_eq!(err, ErrorKind::ParsingFailed);
+    assert_eq!(err.property(), Some("drive-strength"));
+}
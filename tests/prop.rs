@@ -0,0 +1,49 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::traits::HasNamedProperty;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    word = <0x61626300>;
+    bytes = [01 02 03];
+    odd = [00 00 00 01 02];
+    wide = <0x1 0x2>;
+    names = "a", "bc";
+};
+"#;
+
+#[test]
+fn typed_accessors() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let root = tree.root();
+
+    // an integer which happens to look like a string
+    let word = root.find_prop("word").unwrap();
+    assert!(matches!(word.value(), PropertyValue::String("abc")));
+    assert_eq!(word.raw(), b"abc\0");
+    assert_eq!(word.as_u32(), Some(0x6162_6300));
+    assert_eq!(word.as_u64(), Some(0x6162_6300));
+    assert_eq!(word.as_str(), Some("abc"));
+
+    let bytes = root.find_prop("bytes").unwrap();
+    assert_eq!(bytes.as_bytes(), &[1, 2, 3]);
+    assert_eq!(bytes.as_u32(), None);
+    assert!(bytes.as_cells().is_none());
+
+    // a cell and a trailing byte is no list of cells
+    let odd = root.find_prop("odd").unwrap();
+    assert!(odd.as_cells().is_none());
+    assert_eq!(odd.as_u64(), None);
+
+    let wide = root.find_prop("wide").unwrap();
+    assert_eq!(wide.as_u64(), Some(0x1_0000_0002));
+    assert_eq!(wide.as_cells().unwrap().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(wide.as_str(), None);
+
+    let names = root.find_prop("names").unwrap();
+    assert_eq!(names.as_str(), None);
+    assert_eq!(names.as_str_list().collect::<Vec<_>>(), ["a", "bc"]);
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::reg::{PciAddress, PciSpace, RegEntry};
+use dtb_parser::traits::HasNamedProperty;
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+const PCI: &str = r#"
+    /dts-v1/;
+    / {
+        #address-cells = <2>;
+        #size-cells = <2>;
+
+        pci {
+            #address-cells = <3>;
+            #size-cells = <2>;
+            device_type = "pci";
+
+            ethernet@1,2 {
+                reg = <0x00010a00 0 0 0 0>, <0x43010a10 0x1 0x80000000 0 0x100000>;
+            };
+        };
+
+        broken {
+            #address-cells = <0>;
+            #size-cells = <0>;
+
+            child {
+                reg = <1>;
+            };
+        };
+
+        short {
+            reg = <0 0x1000 0>;
+        };
+    };
+"#;
+
+#[test]
+fn sizeless_reg() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let cpu = tree.find_node("/cpus/cpu@0").unwrap();
+    let reg = cpu.find_prop("reg").unwrap();
+    assert_eq!(reg.reg_entries().unwrap(), [RegEntry::new(0, 1, None)]);
+    assert!(matches!(reg.value(), PropertyValue::Reg(entries) if entries[0].size().is_none()));
+
+    let uart = tree.find_node("/soc/uart@10000000").unwrap();
+    assert!(matches!(
+        uart.find_prop("reg").unwrap().value(),
+        PropertyValue::Address(0x10000000, 0x100)
+    ));
+}
+
+#[test]
+fn pci_reg() {
+    let tree = DeviceTree::from_dts(PCI).unwrap();
+    let ethernet = tree.find_node("/pci/ethernet@1,2").unwrap();
+    let reg = ethernet.find_prop("reg").unwrap();
+    let entries = reg.reg_entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].size(), Some(0));
+    assert_eq!(
+        entries[1].cells().collect::<Vec<_>>(),
+        [0x43010a10, 0x1, 0x80000000]
+    );
+    assert_eq!(
+        entries[1].pci(),
+        Some(PciAddress {
+            relocatable: true,
+            prefetchable: true,
+            aliased: false,
+            space: PciSpace::Memory64,
+            bus: 1,
+            device: 1,
+            function: 2,
+            register: 0x10,
+            address: 0x1_8000_0000,
+        })
+    );
+    assert_eq!(entries[0].pci().unwrap().space, PciSpace::Configuration);
+    assert!(matches!(reg.value(), PropertyValue::Reg(it) if it == entries));
+}
+
+#[test]
+fn bad_reg() {
+    let tree = DeviceTree::from_dts(PCI).unwrap();
+    for path in ["/broken/child", "/short"] {
+        let reg = tree.find_node(path).unwrap().find_prop("reg").unwrap();
+        assert!(reg.reg_entries().is_err());
+        assert!(matches!(reg.value(), PropertyValue::Unknown));
+    }
+}
+
+#[test]
+fn encode_reg() {
+    let mut tree = DeviceTree::from_dts(PCI).unwrap();
+    let entries = vec![RegEntry::new(
+        0x0200_0000_0000_0000_4000_0000,
+        3,
+        Some(0x1000),
+    )];
+    let ethernet = tree.find_node_mut("/pci/ethernet@1,2").unwrap();
+    ethernet
+        .set_prop("reg", PropertyValue::Reg(entries.clone()))
+        .unwrap();
+    assert_eq!(
+        ethernet.find_prop("reg").unwrap().reg_entries().unwrap(),
+        entries
+    );
+    assert!(ethernet
+        .set_prop("reg", PropertyValue::Reg(vec![RegEntry::new(0, 3, None)]))
+        .is_err());
+}
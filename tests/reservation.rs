@@ -0,0 +1,99 @@
+use dtb_parser::reservation::MemoryReservation;
+use dtb_parser::Fdt;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+fn read_u32(blob: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(blob[offset..offset + 4].try_into().unwrap())
+}
+
+fn write_u32(blob: &mut [u8], offset: usize, value: u32) {
+    blob[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+/// Inserts reservation entries in front of the terminating entry of the fixture
+fn with_reservations(entries: &[(u64, u64)]) -> Vec<u8> {
+    let off_mem_reserved = read_u32(DTB, 16) as usize;
+    let mut blob = DTB[..off_mem_reserved].to_vec();
+    for (address, size) in entries {
+        blob.extend_from_slice(&address.to_be_bytes());
+        blob.extend_from_slice(&size.to_be_bytes());
+    }
+    blob.extend_from_slice(&DTB[off_mem_reserved..]);
+    let shift = (entries.len() * 16) as u32;
+    for field in [4, 8, 12] {
+        let value = read_u32(&blob, field);
+        write_u32(&mut blob, field, value + shift);
+    }
+    blob
+}
+
+#[test]
+fn empty_block() {
+    let fdt = Fdt::from_bytes(DTB).unwrap();
+    assert_eq!(fdt.memory_reservations().count(), 0);
+}
+
+#[test]
+fn read_entries() {
+    let blob = with_reservations(&[(0x8000_0000, 0x20_0000), (0x8800_0000, 0x1000)]);
+    let fdt = Fdt::from_bytes(&blob).unwrap();
+    let entries = fdt
+        .memory_reservations()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        entries,
+        [
+            MemoryReservation::new(0x8000_0000, 0x20_0000),
+            MemoryReservation::new(0x8800_0000, 0x1000)
+        ]
+    );
+    assert!(fdt.find_node("/soc/uart@10000000").is_some());
+}
+
+#[test]
+fn unterminated_block() {
+    // move the block to the end of the blob, where it ends right after the first entry
+    let mut blob = DTB.to_vec();
+    blob.resize(DTB.len().next_multiple_of(8), 0);
+    let off_mem_reserved = blob.len() as u32;
+    blob.extend_from_slice(&0x8000_0000u64.to_be_bytes());
+    blob.extend_from_slice(&0x1000u64.to_be_bytes());
+    let total_size = blob.len() as u32;
+    write_u32(&mut blob, 4, total_size);
+    write_u32(&mut blob, 16, off_mem_reserved);
+    let fdt = Fdt::from_bytes(&blob).unwrap();
+    let mut entries = fdt.memory_reservations();
+    assert!(matches!(entries.next(), Some(Ok(_))));
+    assert!(matches!(entries.next(), Some(Err(_))));
+    assert!(entries.next().is_none());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn tree_reservations() {
+    use dtb_parser::DeviceTree;
+
+    let blob = with_reservations(&[(0x8000_0000, 0x20_0000)]);
+    let tree = DeviceTree::from_bytes(&blob).unwrap();
+    assert_eq!(
+        tree.memory_reservations().collect::<Vec<_>>(),
+        [MemoryReservation::new(0x8000_0000, 0x20_0000)]
+    );
+    assert!(DeviceTree::from_bytes(DTB)
+        .unwrap()
+        .memory_reservations()
+        .next()
+        .is_none());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn write_reservations() {
+    use dtb_parser::DeviceTree;
+
+    let blob = with_reservations(&[(0x8000_0000, 0x20_0000), (0x8800_0000, 0x1000)]);
+    let tree = DeviceTree::from_bytes(&blob).unwrap();
+    assert_eq!(tree.to_bytes().unwrap(), blob);
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::ErrorKind;
+use dtb_parser::reserved_memory::Placement;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/memreserve/ 0x80000000 0x10000;
+/memreserve/ 0x9ff00000 0x100000;
+/ {
+    #address-cells = <2>;
+    #size-cells = <2>;
+
+    reserved-memory {
+        #address-cells = <2>;
+        #size-cells = <2>;
+        ranges;
+
+        firmware: firmware@80010000 {
+            reg = <0x0 0x80010000 0x0 0x30000>;
+            no-map;
+        };
+
+        ramoops@a0000000 {
+            reg = <0x0 0xa0000000 0x0 0x100000>;
+        };
+
+        linux,cma {
+            compatible = "shared-dma-pool";
+            reusable;
+            size = <0x0 0x4000000>;
+            alignment = <0x0 0x400000>;
+            alloc-ranges = <0x0 0x80000000 0x0 0x40000000>;
+            linux,cma-default;
+        };
+
+        disabled@b0000000 {
+            reg = <0x0 0xb0000000 0x0 0x1000>;
+            status = "disabled";
+        };
+    };
+
+    wifi {
+        memory-region = <&firmware>;
+    };
+};
+"#;
+
+#[test]
+fn regions() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let reserved = tree.reserved_memory().unwrap();
+    assert_eq!(reserved.regions().len(), 3);
+
+    let firmware = reserved.find("firmware@80010000").unwrap();
+    assert_eq!(
+        firmware.placement(),
+        &Placement::Static(vec![(0x8001_0000, 0x30000)])
+    );
+    assert!(firmware.no_map());
+    assert!(!firmware.reusable());
+
+    let cma = reserved.dynamic_regions().collect::<Vec<_>>();
+    assert_eq!(cma.len(), 1);
+    assert_eq!(
+        cma[0].placement(),
+        &Placement::Dynamic {
+            size: 0x400_0000,
+            alignment: Some(0x40_0000),
+            alloc_ranges: vec![(0x8000_0000, 0x4000_0000)],
+        }
+    );
+    assert!(cma[0].shared_dma_pool() && cma[0].reusable() && cma[0].default_pool());
+    assert!(reserved.find("disabled@b0000000").is_none());
+
+    // the firmware follows the first reservation of the header, ramoops the second one
+    assert_eq!(
+        reserved.static_ranges().unwrap(),
+        [(0x8000_0000, 0x40000), (0x9ff0_0000, 0x200000)]
+    );
+}
+
+#[test]
+fn memory_region() {
+    let tree = DeviceTree::from_dts(SOURCE).unwrap();
+    let wifi = tree.node_ref(tree.find_node("/wifi").unwrap()).unwrap();
+    let regions = wifi.memory_regions().unwrap();
+    assert_eq!(regions.len(), 1);
+    assert_eq!(
+        regions[0].node().path(),
+        "/reserved-memory/firmware@80010000"
+    );
+
+    let root = tree.node_ref(tree.root()).unwrap();
+    assert!(root.memory_regions().unwrap().is_empty());
+}
+
+#[test]
+fn malformed_region() {
+    // neither `reg` nor `size`, the other regions are still read
+    let source = SOURCE.replace("size = <0x0 0x4000000>;", "");
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let reserved = tree.reserved_memory().unwrap();
+    assert_eq!(reserved.regions().len(), 2);
+    assert!(reserved.find("linux,cma").is_none());
+    assert_eq!(reserved.errors().len(), 1);
+    let err = &reserved.errors()[0];
+    assert_eq!(*err, ErrorKind::MissingCellParameter);
+    assert_eq!(err.path(), Some("/reserved-memory/linux,cma"));
+    // the fixed ranges cannot be told without it
+    assert_eq!(
+        reserved.static_ranges().err().unwrap(),
+        ErrorKind::MissingCellParameter
+    );
+
+    // it is still an error for the devices using it
+    let source = source
+        .replace("<&firmware>", "<&cma>")
+        .replace("linux,cma {", "cma: linux,cma {");
+    let tree = DeviceTree::from_dts(&source).unwrap();
+    let wifi = tree.node_ref(tree.find_node("/wifi").unwrap()).unwrap();
+    let err = wifi.memory_regions().err().unwrap();
+    assert_eq!(err, ErrorKind::MissingCellParameter);
+    assert_eq!(err.path(), Some("/reserved-memory/linux,cma"));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::device_tree::DeviceTree;
+use dtb_parser::error::Result;
+use dtb_parser::prop::PropertyValue;
+use dtb_parser::schema::{self, DefaultRegistry, PropertyContext, Registry};
+use dtb_parser::traits::{HasNamedChildNode, HasNamedProperty};
+use dtb_parser::walker::Validation;
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/ {
+    #address-cells = <1>;
+    #size-cells = <1>;
+
+    uart@1000 {
+        compatible = "vendor,uart-v2", "vendor,uart";
+        reg = <0x1000 0x100>;
+        vendor,clock-frequencies = <100000000>;
+        vendor,id = <0x61626300>;
+    };
+
+    timer@2000 {
+        compatible = "vendor,timer";
+        reg = <0x2000 0x100>;
+        vendor,id = <0x61626300>;
+    };
+};
+"#;
+
+// a decoder which knows the node's bus
+fn reg_base<'v>(raw: &'v [u8], context: &PropertyContext<'_>) -> Result<PropertyValue<'v>> {
+    match schema::builtin(raw, context)? {
+        PropertyValue::Address(address, _) => Ok(PropertyValue::Integer(address)),
+        other => Ok(other),
+    }
+}
+
+#[test]
+fn registry() {
+    let blob = DeviceTree::from_dts(SOURCE).unwrap().to_bytes().unwrap();
+    let registry = Registry::new()
+        .with_property("vendor,clock-frequencies", schema::integers)
+        .with_binding("vendor,uart", "vendor,id", schema::integers)
+        .with_binding("vendor,timer", "reg", reg_base);
+    let tree = DeviceTree::from_bytes_with(&blob, Validation::Strict, &registry).unwrap();
+
+    let uart = tree.root().find_child("uart@1000").unwrap();
+    assert!(matches!(
+        uart.of_value("vendor,clock-frequencies"),
+        Some(PropertyValue::Integers(it)) if it == [100000000]
+    ));
+    assert!(matches!(
+        uart.of_value("vendor,id"),
+        Some(PropertyValue::Integers(it)) if it == [0x6162_6300]
+    ));
+    assert!(matches!(
+        uart.of_value("reg"),
+        Some(PropertyValue::Address(0x1000, 0x100))
+    ));
+
+    let timer = tree.root().find_child("timer@2000").unwrap();
+    assert!(matches!(
+        timer.of_value("vendor,id"),
+        Some(PropertyValue::String("abc"))
+    ));
+    assert!(matches!(
+        timer.of_value("reg"),
+        Some(PropertyValue::Integer(0x2000))
+    ));
+
+    // the built-in rules guess
+    let tree = DeviceTree::from_bytes_with(&blob, Validation::Lenient, &DefaultRegistry).unwrap();
+    let uart = tree.root().find_child("uart@1000").unwrap();
+    assert!(matches!(
+        uart.of_value("vendor,clock-frequencies"),
+        Some(PropertyValue::Integer(100000000))
+    ));
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::node::DeviceTreeNode;
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+const BUSES: &str = r#"
+    /dts-v1/;
+    / {
+        #address-cells = <2>;
+        #size-cells = <2>;
+
+        soc {
+            #address-cells = <1>;
+            #size-cells = <1>;
+            ranges = <0x0 0x0 0x40000000 0x100000>, <0x200000 0x1 0x0 0x1000>;
+
+            bridge {
+                #address-cells = <1>;
+                #size-cells = <1>;
+                ranges = <0x0 0x80000 0x1000>;
+
+                uart@100 {
+                    reg = <0x100 0x20>, <0x800 0x10>;
+                };
+            };
+
+            gpio@200000 {
+                reg = <0x200000 0x100>;
+            };
+
+            isolated {
+                #address-cells = <1>;
+                #size-cells = <1>;
+
+                timer@0 {
+                    reg = <0x0 0x10>;
+                };
+            };
+        };
+    };
+"#;
+
+#[test]
+fn identity_ranges() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let uart = tree.find_node("/soc/uart@10000000").unwrap();
+    assert_eq!(tree.translate_address(uart, 0x10000000), Some(0x10000000));
+    assert_eq!(tree.cpu_regs(uart).unwrap(), [(0x10000000, 0x100)]);
+    let memory = tree.find_node("/memory@80000000").unwrap();
+    assert_eq!(tree.cpu_regs(memory).unwrap(), [(0x80000000, 0x2000000)]);
+}
+
+#[test]
+fn nested_ranges() {
+    let tree = DeviceTree::from_dts(BUSES).unwrap();
+    let uart = tree.find_node("/soc/bridge/uart@100").unwrap();
+    assert_eq!(tree.translate_address(uart, 0x100), Some(0x40080100));
+    assert_eq!(
+        tree.cpu_regs(uart).unwrap(),
+        [(0x40080100, 0x20), (0x40080800, 0x10)]
+    );
+    assert_eq!(tree.translate_address(uart, 0x1000), None);
+
+    let gpio = tree.find_node("/soc/gpio@200000").unwrap();
+    assert_eq!(tree.cpu_regs(gpio).unwrap(), [(0x100000000, 0x100)]);
+    assert_eq!(tree.translate_address(gpio, 0x100000), None);
+
+    // a bus without ranges cannot be translated through
+    let timer = tree.find_node("/soc/isolated/timer@0").unwrap();
+    assert_eq!(tree.translate_address(timer, 0), None);
+    assert!(tree.cpu_regs(timer).is_none());
+}
+
+#[test]
+fn pci_ranges() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let mut device = DeviceTreeNode::new("device@0");
+    device.set_prop_raw("reg", vec![0u8; 20]);
+    tree.find_node_mut("/soc/pci@30000000")
+        .unwrap()
+        .add_child(device);
+    let device = tree.find_node("/soc/pci@30000000/device@0").unwrap();
+
+    // memory space, the bus and device bits in the high cell do not matter
+    let address = (0x0200_0800u128 << 64) | 0x4000_1000;
+    assert_eq!(tree.translate_address(device, address), Some(0x4000_1000));
+    // io space
+    let address = (0x0100_0000u128 << 64) | 0x10;
+    assert_eq!(tree.translate_address(device, address), Some(0x300_0010));
+    // prefetchable memory above 4G is not in the io space
+    let address = (0x0100_0000u128 << 64) | 0x4_0000_0000;
+    assert_eq!(tree.translate_address(device, address), None);
+}
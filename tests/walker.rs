@@ -0,0 +1,59 @@
+use dtb_parser::walker::Token;
+use dtb_parser::Fdt;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn walk_tokens() {
+    let fdt = Fdt::from_bytes(DTB).unwrap();
+    let mut depth = 0usize;
+    let mut nodes = 0usize;
+    for token in fdt.tokens() {
+        match token.unwrap() {
+            Token::BeginNode(_) => {
+                depth += 1;
+                nodes += 1;
+            }
+            Token::EndNode => depth -= 1,
+            Token::Property(_) => assert!(depth > 0),
+            Token::End => assert_eq!(depth, 0),
+        }
+    }
+    assert_eq!(nodes, 18);
+}
+
+#[test]
+fn find_node() {
+    let fdt = Fdt::from_bytes(DTB).unwrap();
+    let cpu = fdt.find_node("/cpus/cpu@0").unwrap();
+    assert_eq!(cpu.name(), "cpu@0");
+    assert_eq!(
+        cpu.find_prop("mmu-type").unwrap().as_str(),
+        Some("riscv,sv48")
+    );
+    assert!(cpu.find_child("interrupt-controller").is_some());
+    assert!(fdt.find_node("/cpus/cpu@1").is_none());
+}
+
+#[test]
+fn read_cells() {
+    let fdt = Fdt::from_bytes(DTB).unwrap();
+    let cpus = fdt.find_node("/cpus").unwrap();
+    assert_eq!(cpus.address_cells(), Some(1));
+    assert_eq!(cpus.size_cells(), Some(0));
+
+    let flash = fdt.find_node("/flash@20000000").unwrap();
+    let mut reg = flash.reg().unwrap();
+    assert_eq!(reg.next(), Some((0x20000000, 0x2000000)));
+    assert_eq!(reg.next(), Some((0x22000000, 0x2000000)));
+    assert_eq!(reg.next(), None);
+
+    let map_mask = fdt.find_node("/soc/pci@30000000").unwrap();
+    let mut cells = map_mask
+        .find_prop("interrupt-map-mask")
+        .unwrap()
+        .as_cells()
+        .unwrap();
+    assert_eq!(cells.next(), Some(0x1800));
+    assert_eq!(cells.nth(2), Some(0x7));
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "alloc")]
+
+use dtb_parser::error::ErrorKind;
+use dtb_parser::node::DeviceTreeNode;
+use dtb_parser::DeviceTree;
+
+const DTB: &[u8] = include_bytes!("device.dtb");
+
+#[test]
+fn identical_blob() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    assert_eq!(tree.to_bytes().unwrap(), DTB);
+}
+
+#[test]
+fn round_trip() {
+    let tree = DeviceTree::from_bytes(DTB).unwrap();
+    let blob = tree.to_bytes().unwrap();
+    let parsed = DeviceTree::from_bytes(&blob).unwrap();
+    assert_eq!(format!("{}", parsed), format!("{}", tree));
+    assert_eq!(parsed.boot_cpu_id(), tree.boot_cpu_id());
+    assert_eq!(parsed.size_dt_struct(), tree.size_dt_struct());
+}
+
+#[test]
+fn invalid_names() {
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    tree.root_mut()
+        .find_child_mut("chosen")
+        .unwrap()
+        .rename("a/b");
+    let err = tree.to_bytes().unwrap_err();
+    assert_eq!(err, ErrorKind::EncodingFailed);
+    assert_eq!(err.path(), Some("/a/b"));
+
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    tree.root_mut().add_child(DeviceTreeNode::new("a\0b"));
+    assert_eq!(tree.to_bytes().unwrap_err(), ErrorKind::EncodingFailed);
+
+    let mut tree = DeviceTree::from_bytes(DTB).unwrap();
+    let chosen = tree.root_mut().find_child_mut("chosen").unwrap();
+    chosen.set_prop_raw("a\0b", Vec::new());
+    let err = tree.to_bytes().unwrap_err();
+    assert_eq!(err, ErrorKind::EncodingFailed);
+    assert_eq!(err.path(), Some("/chosen"));
+    assert_eq!(err.property(), Some("a\0b"));
+}